@@ -36,11 +36,7 @@ struct TimeWindowU64 {
 
 impl TimeWindowU64 {
     fn lateness(&self, time: u64) -> u64 {
-        if time > self.end {
-            time - self.end
-        } else {
-            0
-        }
+        time.saturating_sub(self.end)
     }
 
     fn contains(&self, time: u64) -> bool {
@@ -48,11 +44,7 @@ impl TimeWindowU64 {
     }
 
     fn waiting_time(&self, time: u64) -> u64 {
-        if time < self.start {
-            self.start - time
-        } else {
-            0
-        }
+        self.start.saturating_sub(time)
     }
 }
 
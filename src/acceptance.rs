@@ -0,0 +1,155 @@
+use rand::Rng;
+
+use crate::penalizer::Objective;
+
+/// Decides from which local optimum the solver continues its search.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Acceptance {
+    /// Keeps no local optimum and restarts from a random solution in every iteration.
+    #[default]
+    Improvement,
+    /// Accepts a worse local optimum with probability exp(-worsening / temperature),
+    /// where the worsening is relative to the current local optimum.
+    /// The temperature is multiplied by the cooling rate after every iteration.
+    SimulatedAnnealing {
+        initial_temperature: f64,
+        cooling_rate: f64,
+    },
+    /// Accepts a local optimum if it is not worse than the current one
+    /// or than the one that was current `history_length` iterations ago.
+    LateAcceptance { history_length: usize },
+}
+
+/// The state of an acceptance strategy during a run of the solver.
+pub struct Acceptor {
+    acceptance: Acceptance,
+    temperature: f64,
+    history: Vec<Objective>,
+    iteration: usize,
+}
+
+impl Acceptor {
+    pub fn new(acceptance: Acceptance) -> Acceptor {
+        let temperature = match acceptance {
+            Acceptance::SimulatedAnnealing {
+                initial_temperature,
+                ..
+            } => initial_temperature,
+            _ => 0.0,
+        };
+        Acceptor {
+            acceptance,
+            temperature,
+            history: vec![],
+            iteration: 0,
+        }
+    }
+
+    pub fn acceptance(&self) -> Acceptance {
+        self.acceptance
+    }
+
+    /// Whether the search continues from the candidate instead of the current local optimum.
+    pub fn accept<R: Rng>(
+        &mut self,
+        candidate: &Objective,
+        current: &Objective,
+        rng: &mut R,
+    ) -> bool {
+        self.iteration += 1;
+        match self.acceptance {
+            Acceptance::Improvement => candidate < current,
+            Acceptance::SimulatedAnnealing { cooling_rate, .. } => {
+                let accepted = candidate <= current || {
+                    let probability = (-worsening(candidate, current) / self.temperature).exp();
+                    rng.gen::<f64>() < probability
+                };
+                self.temperature *= cooling_rate;
+                accepted
+            }
+            Acceptance::LateAcceptance { history_length } => {
+                if self.history.is_empty() {
+                    self.history = vec![*current; history_length.max(1)];
+                }
+                let v = self.iteration % self.history.len();
+                let accepted = candidate <= current || candidate <= &self.history[v];
+                self.history[v] = if accepted { *candidate } else { *current };
+                accepted
+            }
+        }
+    }
+}
+
+/// Relative worsening of the most important level in which the candidate is worse,
+/// or the absolute one if the current level is zero.
+fn worsening(candidate: &Objective, current: &Objective) -> f64 {
+    candidate
+        .iter()
+        .zip(current.iter())
+        .find(|(a, b)| a != b)
+        .map_or(0.0, |(&a, &b)| match b > 0.0 {
+            true => (a - b) / b,
+            false => a - b,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::objective::{Component, ObjectiveFunction};
+
+    /// The levels of the default objective for the given values of the components.
+    fn objective(values: &[(Component, f64)]) -> Objective {
+        ObjectiveFunction::default().evaluate(&Component::values(values))
+    }
+
+    fn distance(distance: f64) -> Objective {
+        objective(&[(Component::Distance, distance)])
+    }
+
+    #[test]
+    fn test_simulated_annealing() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut acceptor = Acceptor::new(Acceptance::SimulatedAnnealing {
+            initial_temperature: 1e-9,
+            cooling_rate: 0.5,
+        });
+        assert!(acceptor.accept(&distance(9.0), &distance(10.0), &mut rng));
+        assert!(acceptor.accept(&distance(10.0), &distance(10.0), &mut rng));
+        // a cold search does not accept any worsening
+        assert!(!acceptor.accept(&distance(11.0), &distance(10.0), &mut rng));
+        // a hot one accepts almost everything
+        let mut acceptor = Acceptor::new(Acceptance::SimulatedAnnealing {
+            initial_temperature: 1e9,
+            cooling_rate: 0.5,
+        });
+        assert!(acceptor.accept(&distance(11.0), &distance(10.0), &mut rng));
+        assert_eq!(acceptor.temperature, 0.5e9);
+    }
+
+    #[test]
+    fn test_late_acceptance() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut acceptor = Acceptor::new(Acceptance::LateAcceptance { history_length: 2 });
+        // the history starts with the first current objective
+        assert!(!acceptor.accept(&distance(11.0), &distance(10.0), &mut rng));
+        assert!(acceptor.accept(&distance(8.0), &distance(10.0), &mut rng));
+        // 9 is worse than the current 8, but not than the 10 of two iterations ago
+        assert!(acceptor.accept(&distance(9.0), &distance(8.0), &mut rng));
+        assert!(!acceptor.accept(&distance(10.0), &distance(9.0), &mut rng));
+    }
+
+    #[test]
+    fn test_worsening() {
+        assert_eq!(
+            worsening(
+                &objective(&[(Component::JobSplits, 1.0), (Component::Distance, 5.0)]),
+                &distance(10.0)
+            ),
+            1.0
+        );
+        assert_eq!(worsening(&distance(15.0), &distance(10.0)), 0.5);
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{
+    input::{error::InputError, Input},
+    objective::{Component, ObjectiveFunction},
+    penalties::distance::DistanceMatrix,
+    route::{Route, Vehicle},
+};
+
+/// Instances with at most this many locations can be solved exactly.
+/// Time and memory grow with 2^n, so beyond this, the heuristic has to do.
+pub const MAX_LOCATIONS: usize = 18;
+
+/// Checks that the input can be solved exactly: only the distance of a single route is minimized,
+/// so there may be neither time nor capacity constraints, optional jobs, several vehicles
+/// nor an objective other than the distance. An initial route would be ignored, so it is rejected as well.
+pub fn validate(input: &Input) -> Result<(), InputError> {
+    let conflict = |other| InputError::ConflictingOptions {
+        field: "exact",
+        other,
+    };
+    if input.time_input.is_some() {
+        return Err(conflict("time_windows"));
+    }
+    if input.capacity_input.is_some() {
+        return Err(conflict("demands, capacity or pickups_deliveries"));
+    }
+    if input.priorities.iter().flatten().any(Option::is_some) {
+        return Err(conflict("priorities"));
+    }
+    if input
+        .vehicles
+        .as_ref()
+        .is_some_and(|vehicles| vehicles.len() != 1)
+    {
+        return Err(conflict("vehicles"));
+    }
+    if !minimizes_distance(&input.objective) {
+        return Err(conflict("objective"));
+    }
+    if input.init_route.is_some() {
+        return Err(conflict("init_route"));
+    }
+    let n = input.distance_matrix.len();
+    if n > MAX_LOCATIONS {
+        return Err(InputError::TooManyLocations {
+            field: "exact",
+            len: n,
+            max: MAX_LOCATIONS,
+        });
+    }
+    Ok(())
+}
+
+/// Whether the objective comes down to the distance, which holds for the default one,
+/// since all other components are zero without time and capacity constraints.
+fn minimizes_distance(objective: &ObjectiveFunction) -> bool {
+    let weights = || objective.levels().iter().flatten();
+    let only_distance = weights().all(|&(component, _)| component == Component::Distance)
+        && weights().any(|&(_, weight)| weight > 0.0);
+    only_distance || *objective == ObjectiveFunction::default()
+}
+
+/// The shortest route over all jobs, for a single vehicle or as a closed tour without one.
+pub fn optimal_route(
+    distance_matrix: &DistanceMatrix,
+    jobs: &[usize],
+    vehicle: Option<Vehicle>,
+) -> Route {
+    match (vehicle, jobs.split_first()) {
+        (Some(vehicle), _) => Route::with_vehicle(
+            shortest_path(distance_matrix, jobs, vehicle.start, vehicle.end),
+            vehicle,
+        ),
+        // a closed tour can be rotated to begin with any job, so it returns to the first one
+        (None, Some((&first, rest))) => {
+            let mut sequence = vec![first];
+            sequence.extend(shortest_path(
+                distance_matrix,
+                rest,
+                Some(first),
+                Some(first),
+            ));
+            Route::new(sequence)
+        }
+        (None, None) => Route::new(vec![]),
+    }
+}
+
+/// The shortest path from `start` through all `jobs` to `end` by the Held–Karp dynamic program,
+/// in O(2^n n^2) time and O(2^n n) memory for n jobs.
+/// Without a start or an end, the path begins at its first or ends at its last job.
+pub fn shortest_path(
+    distance_matrix: &DistanceMatrix,
+    jobs: &[usize],
+    start: Option<usize>,
+    end: Option<usize>,
+) -> Vec<usize> {
+    let n = jobs.len();
+    if n == 0 {
+        return vec![];
+    }
+    let distance = |i: usize, j: usize| distance_matrix.distance(jobs[i], jobs[j]);
+    let full = (1 << n) - 1;
+    // cost[set * n + last] is the length of the shortest path from the start
+    // through the jobs in `set`, ending at the job `last` of the set
+    let mut cost = vec![u64::MAX; (full + 1) * n];
+    for (i, &job) in jobs.iter().enumerate() {
+        cost[(1 << i) * n + i] = start.map_or(0, |start| distance_matrix.distance(start, job));
+    }
+    // a set is always smaller than the sets it is extended to, so it is complete when we get to it
+    for set in 1..full {
+        for last in (0..n).filter(|&last| set & (1 << last) != 0) {
+            let length = cost[set * n + last];
+            for next in (0..n).filter(|&next| set & (1 << next) == 0) {
+                let extended = &mut cost[(set | 1 << next) * n + next];
+                *extended = (*extended).min(length.saturating_add(distance(last, next)));
+            }
+        }
+    }
+    let mut last = (0..n)
+        .min_by_key(|&last| {
+            cost[full * n + last]
+                .saturating_add(end.map_or(0, |end| distance_matrix.distance(jobs[last], end)))
+        })
+        .unwrap();
+    // walk back along the jobs whose paths the optimal one extends
+    let mut sequence = vec![jobs[last]];
+    let mut set = full;
+    while set != 1 << last {
+        let rest = set & !(1 << last);
+        last = (0..n)
+            .filter(|&previous| rest & (1 << previous) != 0)
+            .find(|&previous| {
+                cost[rest * n + previous].saturating_add(distance(previous, last))
+                    == cost[set * n + last]
+            })
+            .unwrap();
+        sequence.push(jobs[last]);
+        set = rest;
+    }
+    sequence.reverse();
+    sequence
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use super::*;
+
+    fn length(distance_matrix: &DistanceMatrix, route: &Route) -> u64 {
+        route
+            .legs()
+            .into_iter()
+            .map(|(from, to)| distance_matrix.distance(from, to))
+            .sum()
+    }
+
+    /// The length of the shortest of all orders of the jobs, found by trying them all.
+    fn brute_force(distance_matrix: &DistanceMatrix, jobs: &[usize], vehicle: Vehicle) -> u64 {
+        fn permutations(jobs: &[usize]) -> Vec<Vec<usize>> {
+            if jobs.is_empty() {
+                return vec![vec![]];
+            }
+            (0..jobs.len())
+                .flat_map(|i| {
+                    let mut rest = jobs.to_vec();
+                    let job = rest.remove(i);
+                    permutations(&rest).into_iter().map(move |mut sequence| {
+                        sequence.insert(0, job);
+                        sequence
+                    })
+                })
+                .collect()
+        }
+        permutations(jobs)
+            .into_iter()
+            .map(|sequence| length(distance_matrix, &Route::with_vehicle(sequence, vehicle)))
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_optimal_route() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..20 {
+            // asymmetric distances between 7 locations, 0 and 6 may be depots
+            let distance_matrix = DistanceMatrix::new(
+                (0..7)
+                    .map(|_| (0..7).map(|_| rng.gen_range(1..100)).collect())
+                    .collect(),
+            );
+            let mut jobs: Vec<usize> = (1..6).collect();
+            jobs.shuffle(&mut rng);
+            for (start, end) in [
+                (Some(0), Some(0)),
+                (Some(0), Some(6)),
+                (Some(0), None),
+                (None, Some(6)),
+                (None, None),
+            ] {
+                let vehicle = Vehicle::with_endpoints(start, end);
+                let route = optimal_route(&distance_matrix, &jobs, Some(vehicle));
+                let mut visited = route.sequence.clone();
+                visited.sort();
+                assert_eq!(visited, (1..6).collect::<Vec<_>>());
+                assert_eq!(
+                    length(&distance_matrix, &route),
+                    brute_force(&distance_matrix, &jobs, vehicle)
+                );
+            }
+            // the closed tour is as long as the shortest route from and back to any of its locations
+            let tour = optimal_route(&distance_matrix, &[0, 1, 2, 3, 4, 5, 6], None);
+            assert_eq!(tour.len(), 7);
+            assert_eq!(
+                length(&distance_matrix, &tour),
+                brute_force(&distance_matrix, &[1, 2, 3, 4, 5, 6], Vehicle::new(0, 0))
+            );
+        }
+        let distance_matrix = DistanceMatrix::new(vec![vec![0]]);
+        assert_eq!(
+            optimal_route(&distance_matrix, &[0], None).sequence,
+            vec![0]
+        );
+        assert!(optimal_route(&distance_matrix, &[], Some(Vehicle::new(0, 0))).is_empty());
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = |n| Input::new(DistanceMatrix::new(vec![vec![0; n]; n]), None, None, None);
+        assert_eq!(validate(&input(MAX_LOCATIONS)), Ok(()));
+        assert_eq!(
+            validate(&input(MAX_LOCATIONS + 1)),
+            Err(InputError::TooManyLocations {
+                field: "exact",
+                len: MAX_LOCATIONS + 1,
+                max: MAX_LOCATIONS
+            })
+        );
+        // a single vehicle and jobs that all have to be visited are fine
+        let single = Input {
+            vehicles: Some(vec![Vehicle::new(0, 0)]),
+            priorities: Some(vec![None; 3]),
+            ..input(3)
+        };
+        assert_eq!(validate(&single), Ok(()));
+        let several = Input {
+            vehicles: Some(vec![Vehicle::new(0, 0), Vehicle::new(1, 1)]),
+            ..input(3)
+        };
+        assert_eq!(
+            validate(&several),
+            Err(InputError::ConflictingOptions {
+                field: "exact",
+                other: "vehicles"
+            })
+        );
+        let optional = Input {
+            priorities: Some(vec![None, Some(1.0), None]),
+            ..input(3)
+        };
+        assert_eq!(
+            validate(&optional),
+            Err(InputError::ConflictingOptions {
+                field: "exact",
+                other: "priorities"
+            })
+        );
+        let none = Input {
+            vehicles: Some(vec![]),
+            ..input(3)
+        };
+        assert!(validate(&none).is_err());
+        // only the distance may be minimized, but it may be weighted
+        let distance = Input {
+            objective: ObjectiveFunction::new(vec![vec![(Component::Distance, 0.5)]]),
+            ..input(3)
+        };
+        assert_eq!(validate(&distance), Ok(()));
+        let duration = Input {
+            objective: ObjectiveFunction::new(vec![vec![
+                (Component::Duration, 1.0),
+                (Component::Distance, 1.0),
+            ]]),
+            ..input(3)
+        };
+        assert_eq!(
+            validate(&duration),
+            Err(InputError::ConflictingOptions {
+                field: "exact",
+                other: "objective"
+            })
+        );
+        let init_route = Input {
+            init_route: Some(Route::new(vec![2, 1, 0])),
+            ..input(3)
+        };
+        assert_eq!(
+            validate(&init_route),
+            Err(InputError::ConflictingOptions {
+                field: "exact",
+                other: "init_route"
+            })
+        );
+    }
+}
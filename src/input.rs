@@ -1,8 +1,22 @@
 // this will be what we get from the outside world and what will be inserted into the solver
 
+pub mod error;
+
+use std::collections::BTreeMap;
+
+use error::InputError;
+
 use crate::{
-    penalties::{self, distance::DistanceMatrix, time::time_input::TimeInput},
-    route::Route,
+    acceptance::Acceptance,
+    objective::{Component, ObjectiveFunction},
+    penalties::{
+        self,
+        capacity::capacity_input::CapacityInput,
+        distance::DistanceMatrix,
+        time::time_input::{RawTimeInput, TimeInput},
+    },
+    route::{Route, Vehicle},
+    schema::Problem,
 };
 
 pub struct Input {
@@ -10,6 +24,25 @@ pub struct Input {
     pub time_input: Option<TimeInput>,
     pub time_limit: Option<chrono::Duration>,
     pub init_route: Option<Route>,
+    /// Without vehicles, a single closed tour over all locations is searched.
+    pub vehicles: Option<Vec<Vehicle>>,
+    /// Maximum number of restarts of the local search.
+    pub iteration_limit: Option<u64>,
+    /// Seed for all random decisions of the solver, random if not given.
+    pub seed: Option<u64>,
+    /// How the solver continues after reaching a local optimum.
+    pub acceptance: Acceptance,
+    /// By what solutions are compared.
+    pub objective: ObjectiveFunction,
+    /// The priority of each optional job, None for jobs that have to be visited.
+    /// Without priorities, all jobs have to be visited.
+    pub priorities: Option<Vec<Option<f64>>>,
+    /// The loads of the jobs and the capacity of the vehicles, without any capacity constraints if None.
+    pub capacity_input: Option<CapacityInput>,
+    /// Whether the route is searched exactly by dynamic programming instead of the local search,
+    /// which only applies to small instances that minimize the distance of a single route,
+    /// as checked by `exact::validate`.
+    pub exact: bool,
 }
 
 impl Input {
@@ -24,24 +57,326 @@ impl Input {
             time_input,
             time_limit,
             init_route,
+            vehicles: None,
+            iteration_limit: None,
+            seed: None,
+            acceptance: Acceptance::Improvement,
+            objective: ObjectiveFunction::default(),
+            priorities: None,
+            capacity_input: None,
+            exact: false,
         }
     }
 }
 
-pub(crate) fn get_input_from_raw(
-    distance_matrix: Vec<Vec<u64>>,
-    duration_matrix: Option<Vec<Vec<u64>>>,
-    job_durations: Option<Vec<u64>>,
-    time_windows: Option<Vec<Vec<(u64, u64)>>>,
-    operation_times: Option<(u64, u64)>,
-    working_days: Option<Vec<bool>>,
-    travel_duration_until_break: Option<u64>,
-    break_duration: Option<u64>,
-    time_limit: Option<u64>,
-    init_route: Option<Vec<usize>>,
-) -> Input {
+/// Checks that a matrix has one row and one column per location.
+fn validate_matrix(field: &'static str, matrix: &[Vec<u64>], n: usize) -> Result<(), InputError> {
+    if matrix.len() != n {
+        return Err(InputError::LengthMismatch {
+            field,
+            len: matrix.len(),
+            expected: n,
+        });
+    }
+    match matrix.iter().position(|row| row.len() != n) {
+        Some(location) => Err(InputError::NonSquareMatrix {
+            field,
+            location,
+            len: matrix[location].len(),
+            expected: n,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn validate_length<T>(
+    field: &'static str,
+    values: &Option<Vec<T>>,
+    n: usize,
+) -> Result<(), InputError> {
+    match values {
+        Some(values) if values.len() != n => Err(InputError::LengthMismatch {
+            field,
+            len: values.len(),
+            expected: n,
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn validate_location(
+    field: &'static str,
+    position: usize,
+    location: usize,
+    n: usize,
+) -> Result<(), InputError> {
+    match location < n {
+        true => Ok(()),
+        false => Err(InputError::LocationOutOfRange {
+            field,
+            position,
+            location,
+        }),
+    }
+}
+
+/// The initial route has to visit every location but the depots and optional jobs exactly once.
+fn validate_init_route(
+    init_route: &[usize],
+    vehicles: &Option<Vec<Vehicle>>,
+    priorities: &Option<Vec<Option<f64>>>,
+    n: usize,
+) -> Result<(), InputError> {
+    let mut visited = vec![false; n];
+    for (position, &location) in init_route.iter().enumerate() {
+        validate_location("init_route", position, location, n)?;
+        if visited[location] {
+            return Err(InputError::DuplicateLocation {
+                field: "init_route",
+                location,
+            });
+        }
+        visited[location] = true;
+    }
+    for vehicle in vehicles.iter().flatten() {
+        for depot in vehicle.start.into_iter().chain(vehicle.end) {
+            visited[depot] = true;
+        }
+    }
+    for (location, priority) in priorities.iter().flatten().enumerate() {
+        visited[location] |= priority.is_some();
+    }
+    match visited.iter().position(|&visited| !visited) {
+        Some(location) => Err(InputError::MissingLocation {
+            field: "init_route",
+            location,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// A fixed start or end location, or an open route, turn the tour into the route of a single vehicle.
+/// With a start location, the route returns there unless it is open or has another end location.
+fn vehicles_from_raw(
+    vehicles: Option<Vec<(Option<usize>, Option<usize>)>>,
+    start_location: Option<usize>,
+    end_location: Option<usize>,
+    open_route: bool,
+) -> Result<Option<Vec<Vehicle>>, InputError> {
+    let single_vehicle = start_location.is_some() || end_location.is_some() || open_route;
+    match (vehicles, single_vehicle) {
+        (Some(_), true) => Err(InputError::ConflictingOptions {
+            field: "vehicles",
+            other: "start_location, end_location or open_route",
+        }),
+        // without any vehicle, no job could be visited
+        (Some(vehicles), false) if vehicles.is_empty() => Err(InputError::InvalidOption {
+            field: "vehicles",
+            value: "[]".to_string(),
+        }),
+        (Some(vehicles), false) => Ok(Some(
+            vehicles
+                .into_iter()
+                .map(|(start, end)| Vehicle::with_endpoints(start, end))
+                .collect(),
+        )),
+        (None, true) if open_route && end_location.is_some() => {
+            Err(InputError::ConflictingOptions {
+                field: "open_route",
+                other: "end_location",
+            })
+        }
+        (None, true) => {
+            let end = match open_route {
+                true => None,
+                false => end_location.or(start_location),
+            };
+            Ok(Some(vec![Vehicle::with_endpoints(start_location, end)]))
+        }
+        (None, false) => Ok(None),
+    }
+}
+
+/// The acceptance strategy named by `acceptance`, with its parameters or their defaults.
+fn acceptance_from_raw(
+    acceptance: Option<String>,
+    initial_temperature: Option<f64>,
+    cooling_rate: Option<f64>,
+    history_length: Option<usize>,
+) -> Result<Acceptance, InputError> {
+    let invalid = |field, value: &dyn std::fmt::Display| InputError::InvalidOption {
+        field,
+        value: value.to_string(),
+    };
+    match acceptance.as_deref() {
+        None | Some("improvement") => Ok(Acceptance::Improvement),
+        Some("simulated_annealing") => {
+            let initial_temperature = initial_temperature.unwrap_or(0.05);
+            if !(initial_temperature > 0.0 && initial_temperature.is_finite()) {
+                return Err(invalid("initial_temperature", &initial_temperature));
+            }
+            let cooling_rate = cooling_rate.unwrap_or(0.99);
+            if !(cooling_rate > 0.0 && cooling_rate <= 1.0) {
+                return Err(invalid("cooling_rate", &cooling_rate));
+            }
+            Ok(Acceptance::SimulatedAnnealing {
+                initial_temperature,
+                cooling_rate,
+            })
+        }
+        Some("late_acceptance") => match history_length.unwrap_or(50) {
+            0 => Err(invalid("history_length", &0)),
+            history_length => Ok(Acceptance::LateAcceptance { history_length }),
+        },
+        Some(acceptance) => Err(invalid("acceptance", &acceptance)),
+    }
+}
+
+/// The objective function given as levels that map the names of soft components to weights,
+/// the default lexicographic order if not given.
+/// The hard components always come first, so they cannot be part of a level.
+fn objective_from_raw(
+    objective: Option<Vec<BTreeMap<String, f64>>>,
+) -> Result<ObjectiveFunction, InputError> {
+    let Some(objective) = objective else {
+        return Ok(ObjectiveFunction::default());
+    };
+    let invalid = |value: String| InputError::InvalidOption {
+        field: "objective",
+        value,
+    };
+    if objective.is_empty() || objective.len() > ObjectiveFunction::MAX_LEVELS {
+        return Err(invalid(format!("with {} levels", objective.len())));
+    }
+    let levels = objective
+        .into_iter()
+        .map(|level| {
+            if level.is_empty() {
+                return Err(invalid("with an empty level".to_string()));
+            }
+            level
+                .into_iter()
+                .map(|(name, weight)| {
+                    let component = Component::from_name(&name)
+                        .filter(|component| !component.is_hard())
+                        .ok_or_else(|| invalid(name))?;
+                    match weight >= 0.0 && weight.is_finite() {
+                        true => Ok((component, weight)),
+                        false => Err(invalid(weight.to_string())),
+                    }
+                })
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(ObjectiveFunction::new(levels))
+}
+
+/// Optional jobs need a priority that is neither negative nor infinite.
+fn validate_priorities(priorities: &Option<Vec<Option<f64>>>) -> Result<(), InputError> {
+    match priorities
+        .iter()
+        .flatten()
+        .flatten()
+        .find(|priority| !(**priority >= 0.0 && priority.is_finite()))
+    {
+        Some(priority) => Err(InputError::InvalidOption {
+            field: "priorities",
+            value: priority.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Validates the raw input and transforms it into the input of the solver.
+pub fn get_input_from_raw(problem: Problem) -> Result<Input, InputError> {
+    let Problem {
+        version: _,
+        distance_matrix,
+        duration_matrix,
+        job_durations,
+        time_windows,
+        operation_times,
+        working_days,
+        travel_duration_until_break,
+        break_duration,
+        time_limit,
+        init_route,
+        vehicles,
+        iteration_limit,
+        seed,
+        start_location,
+        end_location,
+        open_route,
+        acceptance,
+        initial_temperature,
+        cooling_rate,
+        history_length,
+        objective,
+        time_zone,
+        weekly_operation_times,
+        operation_time_exceptions,
+        priorities,
+        horizon_end,
+        max_route_duration,
+        soft_limits,
+        start_time,
+        earliest_departure,
+        soft_time_windows,
+        split_policies,
+        min_split_durations,
+        demands,
+        capacity,
+        pickups_deliveries,
+        precedences,
+        exact,
+    } = problem;
+    let n = distance_matrix.len();
+    if n == 0 {
+        return Err(InputError::EmptyMatrix {
+            field: "distance_matrix",
+        });
+    }
+    validate_matrix("distance_matrix", &distance_matrix, n)?;
+    if let Some(duration_matrix) = &duration_matrix {
+        validate_matrix("duration_matrix", duration_matrix, n)?;
+    }
+    validate_length("job_durations", &job_durations, n)?;
+    validate_length("time_windows", &time_windows, n)?;
+    validate_length("soft_time_windows", &soft_time_windows, n)?;
+    validate_length("split_policies", &split_policies, n)?;
+    validate_length("min_split_durations", &min_split_durations, n)?;
+    validate_length("priorities", &priorities, n)?;
+    validate_length("demands", &demands, n)?;
+    validate_priorities(&priorities)?;
+    let vehicles = vehicles_from_raw(vehicles, start_location, end_location, open_route)?;
+    for (position, vehicle) in vehicles.iter().flatten().enumerate() {
+        for depot in vehicle.start.into_iter().chain(vehicle.end) {
+            validate_location("vehicles", position, depot, n)?;
+        }
+    }
+    // depots are never served, so a precedence between them could never be enforced
+    for (position, &(before, after, _, _)) in precedences.iter().flatten().enumerate() {
+        for location in [before, after] {
+            validate_location("precedences", position, location, n)?;
+            if vehicles
+                .iter()
+                .flatten()
+                .any(|vehicle| vehicle.is_depot(location))
+            {
+                return Err(InputError::DepotLocation {
+                    field: "precedences",
+                    position,
+                    location,
+                });
+            }
+        }
+    }
+    if let Some(init_route) = &init_route {
+        validate_init_route(init_route, &vehicles, &priorities, n)?;
+    }
+
     let real_distance_matrix = DistanceMatrix::new(distance_matrix);
-    let time_input = penalties::time::time_input::transform(
+    let time_input = penalties::time::time_input::transform(RawTimeInput {
         duration_matrix,
         job_durations,
         time_windows,
@@ -49,14 +384,318 @@ pub(crate) fn get_input_from_raw(
         working_days,
         travel_duration_until_break,
         break_duration,
-    );
-    let time_limit = match time_limit {
-        Some(limit) => Some(chrono::Duration::seconds(limit as i64)),
-        None => None,
-    };
-    let init_route = match init_route {
-        Some(route) => Some(Route::new(route)),
-        None => None,
+        time_zone,
+        weekly_operation_times,
+        operation_time_exceptions,
+        horizon_end,
+        max_route_duration,
+        soft_limits,
+        start_time,
+        earliest_departure,
+        soft_time_windows,
+        split_policies,
+        min_split_durations,
+        precedences,
+    })?;
+    let capacity_input = penalties::capacity::capacity_input::transform(
+        demands,
+        capacity,
+        pickups_deliveries,
+        &vehicles,
+        n,
+    )?;
+    let time_limit = time_limit
+        .map(|limit| penalties::time::time_input::seconds("time_limit", None, limit))
+        .transpose()?;
+    let acceptance = acceptance_from_raw(
+        acceptance,
+        initial_temperature,
+        cooling_rate,
+        history_length,
+    )?;
+    let objective = objective_from_raw(objective)?;
+    let init_route = init_route.map(Route::new);
+    let input = Input {
+        vehicles,
+        iteration_limit,
+        seed,
+        acceptance,
+        objective,
+        priorities,
+        capacity_input,
+        exact,
+        ..Input::new(real_distance_matrix, time_input, time_limit, init_route)
     };
-    Input::new(real_distance_matrix, time_input, time_limit, init_route)
+    if exact {
+        crate::exact::validate(&input)?;
+    }
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_input(
+        distance_matrix: Vec<Vec<u64>>,
+        job_durations: Option<Vec<u64>>,
+        init_route: Option<Vec<usize>>,
+        vehicles: Option<Vec<(Option<usize>, Option<usize>)>>,
+    ) -> Result<Input, InputError> {
+        Problem {
+            distance_matrix,
+            job_durations,
+            init_route,
+            vehicles,
+            ..Problem::default()
+        }
+        .into_input()
+    }
+
+    #[test]
+    fn test_validation() {
+        let matrix = || vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]];
+        assert!(raw_input(matrix(), None, Some(vec![2, 0, 1]), None).is_ok());
+        assert_eq!(
+            raw_input(vec![], None, None, None).err(),
+            Some(InputError::EmptyMatrix {
+                field: "distance_matrix"
+            })
+        );
+        assert_eq!(
+            raw_input(vec![vec![0, 1], vec![1]], None, None, None).err(),
+            Some(InputError::NonSquareMatrix {
+                field: "distance_matrix",
+                location: 1,
+                len: 1,
+                expected: 2
+            })
+        );
+        assert_eq!(
+            raw_input(matrix(), Some(vec![1, 2]), None, None).err(),
+            Some(InputError::LengthMismatch {
+                field: "job_durations",
+                len: 2,
+                expected: 3
+            })
+        );
+        assert_eq!(
+            raw_input(matrix(), None, Some(vec![0, 3, 1]), None).err(),
+            Some(InputError::LocationOutOfRange {
+                field: "init_route",
+                position: 1,
+                location: 3
+            })
+        );
+        assert_eq!(
+            raw_input(matrix(), None, Some(vec![0, 1, 1]), None).err(),
+            Some(InputError::DuplicateLocation {
+                field: "init_route",
+                location: 1
+            })
+        );
+        assert_eq!(
+            raw_input(matrix(), None, Some(vec![0, 1]), None).err(),
+            Some(InputError::MissingLocation {
+                field: "init_route",
+                location: 2
+            })
+        );
+        // depots do not have to be part of the initial route
+        assert!(raw_input(
+            matrix(),
+            None,
+            Some(vec![1, 2]),
+            Some(vec![(Some(0), Some(0))])
+        )
+        .is_ok());
+        // optional jobs do not have to be part of it either
+        assert_eq!(
+            validate_init_route(&[0, 1], &None, &Some(vec![None, None, Some(2.0)]), 3),
+            Ok(())
+        );
+        assert_eq!(
+            validate_init_route(&[0, 2], &None, &Some(vec![None, None, Some(2.0)]), 3),
+            Err(InputError::MissingLocation {
+                field: "init_route",
+                location: 1
+            })
+        );
+        assert_eq!(
+            validate_priorities(&Some(vec![None, Some(-1.0)])),
+            Err(InputError::InvalidOption {
+                field: "priorities",
+                value: "-1".into()
+            })
+        );
+        assert_eq!(
+            raw_input(
+                matrix(),
+                None,
+                None,
+                Some(vec![(Some(0), Some(0)), (Some(1), Some(5))])
+            )
+            .err(),
+            Some(InputError::LocationOutOfRange {
+                field: "vehicles",
+                position: 1,
+                location: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_precedences_at_depots() {
+        let matrix = vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]];
+        let precedences = |vehicles| {
+            Problem {
+                distance_matrix: matrix.clone(),
+                duration_matrix: Some(matrix.clone()),
+                job_durations: Some(vec![0; 3]),
+                time_windows: Some(vec![Some(vec![(1735718400, 1735747200)]); 3]),
+                vehicles,
+                precedences: Some(vec![(1, 2, None, None), (2, 0, None, None)]),
+                ..Problem::default()
+            }
+            .into_input()
+            .err()
+        };
+        assert_eq!(precedences(None), None);
+        assert_eq!(
+            precedences(Some(vec![(Some(0), None)])),
+            Some(InputError::DepotLocation {
+                field: "precedences",
+                position: 1,
+                location: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_endpoints() {
+        let matrix = || vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]];
+        let endpoints = |start_location, end_location, open_route| {
+            Problem {
+                distance_matrix: matrix(),
+                start_location,
+                end_location,
+                open_route,
+                ..Problem::default()
+            }
+            .into_input()
+            .map(|input| input.vehicles)
+        };
+        assert_eq!(endpoints(None, None, false), Ok(None));
+        assert_eq!(
+            endpoints(Some(0), None, false),
+            Ok(Some(vec![Vehicle::new(0, 0)]))
+        );
+        assert_eq!(
+            endpoints(Some(0), Some(2), false),
+            Ok(Some(vec![Vehicle::new(0, 2)]))
+        );
+        assert_eq!(
+            endpoints(Some(0), None, true),
+            Ok(Some(vec![Vehicle::with_endpoints(Some(0), None)]))
+        );
+        assert_eq!(
+            endpoints(None, None, true),
+            Ok(Some(vec![Vehicle::with_endpoints(None, None)]))
+        );
+        assert_eq!(
+            endpoints(None, Some(2), true),
+            Err(InputError::ConflictingOptions {
+                field: "open_route",
+                other: "end_location"
+            })
+        );
+        assert_eq!(
+            vehicles_from_raw(Some(vec![]), None, None, false),
+            Err(InputError::InvalidOption {
+                field: "vehicles",
+                value: "[]".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_acceptance() {
+        assert_eq!(
+            acceptance_from_raw(None, Some(1.0), None, None),
+            Ok(Acceptance::Improvement)
+        );
+        assert_eq!(
+            acceptance_from_raw(Some("simulated_annealing".into()), None, Some(0.5), None),
+            Ok(Acceptance::SimulatedAnnealing {
+                initial_temperature: 0.05,
+                cooling_rate: 0.5
+            })
+        );
+        assert_eq!(
+            acceptance_from_raw(Some("late_acceptance".into()), None, None, Some(10)),
+            Ok(Acceptance::LateAcceptance { history_length: 10 })
+        );
+        assert_eq!(
+            acceptance_from_raw(Some("simulated_annealing".into()), None, Some(1.5), None),
+            Err(InputError::InvalidOption {
+                field: "cooling_rate",
+                value: "1.5".into()
+            })
+        );
+        assert_eq!(
+            acceptance_from_raw(Some("late_acceptance".into()), None, None, Some(0)),
+            Err(InputError::InvalidOption {
+                field: "history_length",
+                value: "0".into()
+            })
+        );
+        assert_eq!(
+            acceptance_from_raw(Some("tabu".into()), None, None, None),
+            Err(InputError::InvalidOption {
+                field: "acceptance",
+                value: "tabu".into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_objective() {
+        assert_eq!(objective_from_raw(None), Ok(ObjectiveFunction::default()));
+        assert_eq!(
+            objective_from_raw(Some(vec![
+                BTreeMap::from([("job_splits".to_string(), 1.0)]),
+                BTreeMap::from([
+                    ("lateness".to_string(), 1.0),
+                    ("distance".to_string(), 0.01)
+                ]),
+            ])),
+            Ok(ObjectiveFunction::new(vec![
+                vec![(Component::JobSplits, 1.0)],
+                vec![(Component::Distance, 0.01), (Component::Lateness, 1.0)],
+            ]))
+        );
+        assert_eq!(
+            objective_from_raw(Some(vec![BTreeMap::from([("km".to_string(), 1.0)])])),
+            Err(InputError::InvalidOption {
+                field: "objective",
+                value: "km".into()
+            })
+        );
+        assert_eq!(
+            objective_from_raw(Some(vec![BTreeMap::from([("distance".to_string(), -1.0)])])),
+            Err(InputError::InvalidOption {
+                field: "objective",
+                value: "-1".into()
+            })
+        );
+        assert_eq!(
+            objective_from_raw(Some(vec![BTreeMap::from([("overload".to_string(), 1.0)])])),
+            Err(InputError::InvalidOption {
+                field: "objective",
+                value: "overload".into()
+            })
+        );
+        assert!(objective_from_raw(Some(vec![])).is_err());
+        assert!(objective_from_raw(Some(vec![BTreeMap::new()])).is_err());
+    }
 }
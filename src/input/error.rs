@@ -0,0 +1,244 @@
+use std::fmt;
+
+/// Everything that can be wrong with the raw input of the solver.
+/// Each variant names the field and, where it applies, the location index at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    /// The distance matrix has no locations at all.
+    EmptyMatrix { field: &'static str },
+    /// A row of a matrix does not have one entry per location.
+    NonSquareMatrix {
+        field: &'static str,
+        location: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// A per-location input does not have one entry per location.
+    LengthMismatch {
+        field: &'static str,
+        len: usize,
+        expected: usize,
+    },
+    /// The demands of a location do not have one entry per dimension of the capacity.
+    DimensionMismatch {
+        field: &'static str,
+        location: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// No location has a time window and no start time is given, so routes cannot be scheduled.
+    NoTimeWindows,
+    /// A time window ends before it starts.
+    InvalidTimeWindow {
+        location: usize,
+        window: usize,
+        start: u64,
+        end: u64,
+    },
+    /// A time window starts before the previous one of the same location ends.
+    UnorderedTimeWindows { location: usize, window: usize },
+    /// The preferred time window of a location or its costs are invalid.
+    InvalidSoftTimeWindow {
+        location: usize,
+        reason: &'static str,
+    },
+    /// A precedence between two jobs is invalid.
+    InvalidPrecedence {
+        precedence: usize,
+        reason: &'static str,
+    },
+    /// A timestamp cannot be represented as a date.
+    TimestampOutOfRange {
+        field: &'static str,
+        location: Option<usize>,
+        value: u64,
+    },
+    /// A duration in seconds is too large to be represented.
+    DurationOutOfRange {
+        field: &'static str,
+        location: Option<usize>,
+        value: u64,
+    },
+    /// The start or end of a shift is not a time of day.
+    InvalidOperationTimes { start: u64, end: u64 },
+    /// Working days are given for more than the seven days of a week, or none is a working day.
+    InvalidWorkingDays { len: usize },
+    /// Weekly operation times are not given for exactly the seven days of a week, or have no shift at all.
+    InvalidWeeklyOperationTimes { len: usize },
+    /// A shift of a day starts before the previous one ends, which may be one over midnight of the day before.
+    UnorderedShifts { field: &'static str, day: String },
+    /// A location index is not part of the distance matrix.
+    LocationOutOfRange {
+        field: &'static str,
+        position: usize,
+        location: usize,
+    },
+    /// A location that has to be visited is missing.
+    MissingLocation {
+        field: &'static str,
+        location: usize,
+    },
+    /// Two options are given that cannot be used together.
+    ConflictingOptions {
+        field: &'static str,
+        other: &'static str,
+    },
+    /// A location that is a depot of a vehicle is given where only jobs may be.
+    DepotLocation {
+        field: &'static str,
+        position: usize,
+        location: usize,
+    },
+    /// A location appears more than once where it may only appear once.
+    DuplicateLocation {
+        field: &'static str,
+        location: usize,
+    },
+    /// A pickup and delivery pair delivers a different load than it picks up, or its pickup delivers.
+    UnbalancedPickupDelivery { pair: usize },
+    /// An option of the solver does not support this many locations.
+    TooManyLocations {
+        field: &'static str,
+        len: usize,
+        max: usize,
+    },
+    /// An option of the solver has a value it does not support.
+    InvalidOption { field: &'static str, value: String },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::EmptyMatrix { field } => write!(f, "{field} must not be empty"),
+            InputError::NonSquareMatrix {
+                field,
+                location,
+                len,
+                expected,
+            } => write!(
+                f,
+                "{field}[{location}] has {len} entries, but there are {expected} locations"
+            ),
+            InputError::LengthMismatch {
+                field,
+                len,
+                expected,
+            } => write!(
+                f,
+                "{field} has {len} entries, but there are {expected} locations"
+            ),
+            InputError::DimensionMismatch {
+                field,
+                location,
+                len,
+                expected,
+            } => write!(
+                f,
+                "{field}[{location}] has {len} dimensions, but the capacity has {expected}"
+            ),
+            InputError::NoTimeWindows => write!(
+                f,
+                "start_time is required, since no location has a time window"
+            ),
+            InputError::InvalidTimeWindow {
+                location,
+                window,
+                start,
+                end,
+            } => write!(
+                f,
+                "time_windows[{location}][{window}] ends at {end} before it starts at {start}"
+            ),
+            InputError::UnorderedTimeWindows { location, window } => write!(
+                f,
+                "time_windows[{location}][{window}] starts before the previous time window ends"
+            ),
+            InputError::InvalidSoftTimeWindow { location, reason } => {
+                write!(f, "soft_time_windows[{location}] {reason}")
+            }
+            InputError::InvalidPrecedence { precedence, reason } => {
+                write!(f, "precedences[{precedence}] {reason}")
+            }
+            InputError::TimestampOutOfRange {
+                field,
+                location: Some(location),
+                value,
+            } => write!(
+                f,
+                "{field}[{location}] contains the timestamp {value}, which is out of range"
+            ),
+            InputError::TimestampOutOfRange {
+                field,
+                location: None,
+                value,
+            } => write!(f, "{field} at timestamp {value} is out of range"),
+            InputError::DurationOutOfRange {
+                field,
+                location: Some(location),
+                value,
+            } => write!(
+                f,
+                "{field}[{location}] contains the duration {value}, which is out of range"
+            ),
+            InputError::DurationOutOfRange {
+                field,
+                location: None,
+                value,
+            } => write!(f, "{field} of {value} seconds is out of range"),
+            InputError::InvalidOperationTimes { start, end } => write!(
+                f,
+                "operation times ({start}, {end}) must satisfy start < 86400 and end <= 86400"
+            ),
+            InputError::InvalidWorkingDays { len } => write!(
+                f,
+                "working_days has {len} entries, but must have at most 7 with at least one working day"
+            ),
+            InputError::InvalidWeeklyOperationTimes { len } => write!(
+                f,
+                "weekly_operation_times has {len} entries, but must have 7 with at least one shift"
+            ),
+            InputError::UnorderedShifts { field, day } => write!(
+                f,
+                "{field}[{day}] has a shift that starts before the previous one ends"
+            ),
+            InputError::LocationOutOfRange {
+                field,
+                position,
+                location,
+            } => write!(
+                f,
+                "{field}[{position}] refers to location {location}, which does not exist"
+            ),
+            InputError::MissingLocation { field, location } => {
+                write!(f, "{field} does not contain location {location}")
+            }
+            InputError::ConflictingOptions { field, other } => {
+                write!(f, "{field} cannot be combined with {other}")
+            }
+            InputError::DepotLocation {
+                field,
+                position,
+                location,
+            } => write!(
+                f,
+                "{field}[{position}] refers to location {location}, which is a depot of a vehicle"
+            ),
+            InputError::DuplicateLocation { field, location } => {
+                write!(f, "{field} contains location {location} more than once")
+            }
+            InputError::UnbalancedPickupDelivery { pair } => write!(
+                f,
+                "pickups_deliveries[{pair}] must pick up (negative) exactly what it delivers (positive)"
+            ),
+            InputError::TooManyLocations { field, len, max } => write!(
+                f,
+                "{field} supports at most {max} locations, but there are {len}"
+            ),
+            InputError::InvalidOption { field, value } => {
+                write!(f, "{field} does not support the value {value}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
@@ -1,34 +1,107 @@
-// mod time_windows;
-mod input;
-mod local_moves;
-mod output;
-mod penalizer;
-mod penalties;
-mod py_output;
-mod route;
-mod solver;
+pub mod acceptance;
+pub mod exact;
+pub mod input;
+pub mod local_moves;
+pub mod objective;
+pub mod output;
+pub mod penalizer;
+pub mod penalties;
+pub mod py_error;
+pub mod py_output;
+pub mod route;
+pub mod schema;
+pub mod solver;
 
-use py_output::PyOutput;
+use std::collections::BTreeMap;
+
+use output::Output;
+use penalties::time::time_input::{RawPrecedence, RawSoftWindow, RawTimeWindows};
+use py_output::{PyDroppedJob, PyJobPenalty, PyOutput, PyPrecedenceViolation, PyRoute, PyStop};
 use pyo3::prelude::*;
+use schema::Problem;
 
 use solver::Solver;
 
 /// Solving the Traveling Salesman Problem with Time Windows.
+/// All arguments but `distance_matrix` are optional. Times are Unix timestamps, durations are seconds,
+/// and times of day are seconds since midnight, local to the IANA `time_zone` (UTC by default).
+///
+/// Schedule: `duration_matrix` and `job_durations` make up the time model, which every other schedule
+/// option needs. `time_windows` are a list of (start, end) windows per location, `None` for any time;
+/// without them, every location can be visited at any time, starting at `start_time`.
+/// The vehicles work within `operation_times` (start, end) on `working_days` (Monday first), or within
+/// the shifts of `weekly_operation_times` (seven lists of (start, end)), with `operation_time_exceptions`
+/// mapping dates like "2025-12-25" to their shifts. They take a `break_duration` after each
+/// `travel_duration_until_break`, start at `start_time` or in time for their first job, but not before
+/// `earliest_departure`, and should end by `horizon_end` within `max_route_duration`. A route that exceeds
+/// them is still scheduled in full, the excess counts as "overtime", or as "excess" with `soft_limits`.
+/// `soft_time_windows` are per location (start, end, earliness, lateness), the costs being lists of
+/// (seconds, cost) points. `split_policies` are "allow", "forbid" or "across_days" per job, with parts
+/// of at least `min_split_durations`. `precedences` are (before, after, min_lag, max_lag) between jobs.
+///
+/// Routes: `vehicles` are (start, end) depots, either may be `None`; for a single route,
+/// `start_location`, `end_location` and `open_route` set them instead. `priorities` makes jobs optional,
+/// `None` for a job that has to be visited. `demands` per location are delivered (positive)
+/// or picked up (negative) in each dimension of `capacity`, and `pickups_deliveries` are (pickup, delivery)
+/// pairs served by the same route.
+///
+/// Search: `time_limit`, `iteration_limit`, `seed`, `init_route` and `acceptance`, which is "improvement",
+/// "simulated_annealing" with `initial_temperature` and `cooling_rate`, or "late_acceptance" with
+/// `history_length`. `exact=True` solves a single route over at most 18 locations that only minimizes
+/// the distance by dynamic programming, and rejects any other instance.
+///
+/// Objective: the hard components "split_violations", "precedence_violations", "pickup_delivery_violations",
+/// "overload" and "overtime" are always minimized first, in this order. `objective` is a list of levels,
+/// compared lexicographically, each a dict that maps soft components to weights, e.g.
+/// `[{"lateness": 1.0, "distance": 0.01}]`. The soft components are "job_splits", "lateness", "excess",
+/// "dropped_priority", "soft_window_cost", "traveling_time", "duration", "waiting_time" and "distance",
+/// by default each a level of its own, in this order.
+///
+/// Invalid input raises a subclass of `InputError`, which itself is a `ValueError`.
 #[pyfunction]
-#[pyo3(signature = (distance_matrix, duration_matrix=None, job_durations=None, time_windows=None, operation_times=None, working_days=None, travel_duration_until_break=None, break_duration=None, time_limit=None, init_route=None))]
+#[pyo3(signature = (distance_matrix, duration_matrix=None, job_durations=None, time_windows=None, operation_times=None, working_days=None, travel_duration_until_break=None, break_duration=None, time_limit=None, init_route=None, vehicles=None, iteration_limit=None, seed=None, start_location=None, end_location=None, open_route=false, acceptance=None, initial_temperature=None, cooling_rate=None, history_length=None, objective=None, time_zone=None, weekly_operation_times=None, operation_time_exceptions=None, priorities=None, horizon_end=None, max_route_duration=None, soft_limits=false, start_time=None, earliest_departure=None, soft_time_windows=None, split_policies=None, min_split_durations=None, demands=None, capacity=None, pickups_deliveries=None, precedences=None, exact=false))]
+#[allow(clippy::too_many_arguments)]
 fn solve(
     distance_matrix: Vec<Vec<u64>>,
     duration_matrix: Option<Vec<Vec<u64>>>,
     job_durations: Option<Vec<u64>>,
-    time_windows: Option<Vec<Vec<(u64, u64)>>>,
+    time_windows: Option<Vec<RawTimeWindows>>,
     operation_times: Option<(u64, u64)>,
     working_days: Option<Vec<bool>>,
     travel_duration_until_break: Option<u64>,
     break_duration: Option<u64>,
     time_limit: Option<u64>,
     init_route: Option<Vec<usize>>,
+    vehicles: Option<Vec<(Option<usize>, Option<usize>)>>,
+    iteration_limit: Option<u64>,
+    seed: Option<u64>,
+    start_location: Option<usize>,
+    end_location: Option<usize>,
+    open_route: bool,
+    acceptance: Option<String>,
+    initial_temperature: Option<f64>,
+    cooling_rate: Option<f64>,
+    history_length: Option<usize>,
+    objective: Option<Vec<BTreeMap<String, f64>>>,
+    time_zone: Option<String>,
+    weekly_operation_times: Option<Vec<Vec<(u64, u64)>>>,
+    operation_time_exceptions: Option<BTreeMap<String, Vec<(u64, u64)>>>,
+    priorities: Option<Vec<Option<f64>>>,
+    horizon_end: Option<u64>,
+    max_route_duration: Option<u64>,
+    soft_limits: bool,
+    start_time: Option<u64>,
+    earliest_departure: Option<u64>,
+    soft_time_windows: Option<Vec<RawSoftWindow>>,
+    split_policies: Option<Vec<String>>,
+    min_split_durations: Option<Vec<u64>>,
+    demands: Option<Vec<Vec<i64>>>,
+    capacity: Option<Vec<u64>>,
+    pickups_deliveries: Option<Vec<(usize, usize)>>,
+    precedences: Option<Vec<RawPrecedence>>,
+    exact: bool,
 ) -> PyResult<PyOutput> {
-    let input = input::get_input_from_raw(
+    let input = Problem {
         distance_matrix,
         duration_matrix,
         job_durations,
@@ -39,16 +112,49 @@ fn solve(
         break_duration,
         time_limit,
         init_route,
-    );
+        vehicles,
+        iteration_limit,
+        seed,
+        start_location,
+        end_location,
+        open_route,
+        acceptance,
+        initial_temperature,
+        cooling_rate,
+        history_length,
+        objective,
+        time_zone,
+        weekly_operation_times,
+        operation_time_exceptions,
+        priorities,
+        horizon_end,
+        max_route_duration,
+        soft_limits,
+        start_time,
+        earliest_departure,
+        soft_time_windows,
+        split_policies,
+        min_split_durations,
+        demands,
+        capacity,
+        pickups_deliveries,
+        precedences,
+        exact,
+        ..Problem::default()
+    }
+    .into_input()?;
     let mut solver = Solver::new(input);
     solver.solve();
 
-    //Ok(solver.get_best_sequence())
-    Ok(PyOutput::new(
-        solver.best_solution.clone(),
-        solver.iterations,
-        solver.time_taken,
-    ))
+    Ok(PyOutput::new(Output {
+        solution: solver.best_solution.clone(),
+        objective: solver.objective(),
+        dropped: solver.dropped_jobs(),
+        precedence_violations: solver.precedence_violations(),
+        optimal: solver.optimal,
+        iterations: solver.iterations,
+        time_taken: solver.time_taken,
+    }))
 }
 
 /// A Python module implemented in Rust.
@@ -56,5 +162,11 @@ fn solve(
 fn traveling_rustling(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(solve, m)?)?;
     m.add_class::<PyOutput>()?;
+    m.add_class::<PyRoute>()?;
+    m.add_class::<PyDroppedJob>()?;
+    m.add_class::<PyJobPenalty>()?;
+    m.add_class::<PyStop>()?;
+    m.add_class::<PyPrecedenceViolation>()?;
+    py_error::register(m)?;
     Ok(())
 }
@@ -1,4 +1,5 @@
 use crate::route::Route;
+// The depots of a vehicle are not part of its sequence, so the moves never change a pinned start or end.
 /// for all moves it holdds that 0 <= i < j < n.
 pub fn two_opt(route: &mut Route, i: usize, j: usize) {
     route.sequence[i..j + 1].reverse();
@@ -32,6 +33,93 @@ pub fn three_shift_right(route: &mut Route, i: usize, j: usize) {
     route.sequence[i..j + 1].rotate_right(3);
 }
 
+/// Or-opt: moves the first k locations between i and j reversed behind j.
+/// Without the reversal, this is the shift by k to the left.
+pub fn reversed_shift_left(route: &mut Route, i: usize, j: usize, k: usize) {
+    route.sequence[i..i + k].reverse();
+    route.sequence[i..j + 1].rotate_left(k);
+}
+
+/// Or-opt: moves the last k locations between i and j reversed in front of i.
+/// Without the reversal, this is the shift by k to the right.
+pub fn reversed_shift_right(route: &mut Route, i: usize, j: usize, k: usize) {
+    route.sequence[j + 1 - k..j + 1].reverse();
+    route.sequence[i..j + 1].rotate_right(k);
+}
+
+/// The ways 3-opt reconnects the segments B = [i, j) and C = [j, k] of a route A B C D
+/// with at least one of them reversed, apart from those that 2-opt already covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreeOpt {
+    /// A B' C' D
+    ReverseBoth,
+    /// A C B' D
+    ReverseFirst,
+    /// A C' B D
+    ReverseSecond,
+}
+
+/// 3-opt with 0 <= i < j <= k < n.
+pub fn three_opt(route: &mut Route, i: usize, j: usize, k: usize, reconnection: ThreeOpt) {
+    let sequence = &mut route.sequence;
+    match reconnection {
+        ThreeOpt::ReverseBoth => {
+            sequence[i..j].reverse();
+            sequence[j..k + 1].reverse();
+        }
+        ThreeOpt::ReverseFirst => {
+            sequence[i..j].reverse();
+            sequence[i..k + 1].rotate_left(j - i);
+        }
+        ThreeOpt::ReverseSecond => {
+            sequence[j..k + 1].reverse();
+            sequence[i..k + 1].rotate_left(j - i);
+        }
+    }
+}
+
+// Inter-route moves work on two different routes,
+// i is a position in the first and j a position in the second route.
+// They leave the first route before position i and the second before position j untouched.
+
+/// Moves the i-th location of `from` to position j of `to`, where 0 <= j <= to.len().
+pub fn relocate(from: &mut Route, to: &mut Route, i: usize, j: usize) {
+    let location = from.sequence.remove(i);
+    to.sequence.insert(j, location);
+}
+
+/// Exchanges the i-th location of `route1` with the j-th location of `route2`.
+pub fn exchange(route1: &mut Route, route2: &mut Route, i: usize, j: usize) {
+    std::mem::swap(&mut route1.sequence[i], &mut route2.sequence[j]);
+}
+
+/// Exchanges the tails `route1[i..]` and `route2[j..]` (2-opt*),
+/// where 0 <= i <= route1.len() and 0 <= j <= route2.len().
+pub fn cross(route1: &mut Route, route2: &mut Route, i: usize, j: usize) {
+    let tail1 = route1.sequence.split_off(i);
+    let tail2 = route2.sequence.split_off(j);
+    route1.sequence.extend(tail2);
+    route2.sequence.extend(tail1);
+}
+
+// Removing and inserting change which optional jobs are visited.
+
+/// Removes the i-th location of the route.
+pub fn remove(route: &mut Route, i: usize) {
+    route.sequence.remove(i);
+}
+
+/// Inserts `location` at position j of the route, where 0 <= j <= route.len().
+pub fn insert(route: &mut Route, j: usize, location: usize) {
+    route.sequence.insert(j, location);
+}
+
+/// Exchanges the neighbouring segments [i, j) and [j, k) of the route.
+/// Local search does not easily undo this, so it is used to perturb a local optimum.
+pub fn double_bridge(route: &mut Route, i: usize, j: usize, k: usize) {
+    route.sequence[i..k].rotate_left(j - i);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,4 +161,76 @@ mod tests {
         three_shift_left(&mut route, 1, 3);
         assert_eq!(route.sequence, vec![0, 1, 2, 3, 4]);
     }
+
+    #[test]
+    fn test_reversed_shift() {
+        let mut route = Route::new(vec![0, 1, 2, 3, 4, 5]);
+        reversed_shift_left(&mut route, 1, 4, 2);
+        assert_eq!(route.sequence, vec![0, 3, 4, 2, 1, 5]);
+        let mut route = Route::new(vec![0, 1, 2, 3, 4, 5]);
+        reversed_shift_right(&mut route, 1, 4, 3);
+        assert_eq!(route.sequence, vec![0, 4, 3, 2, 1, 5]);
+        // a single location is moved just like by a shift
+        let mut route = Route::new(vec![0, 1, 2, 3, 4, 5]);
+        reversed_shift_left(&mut route, 1, 4, 1);
+        assert_eq!(route.sequence, vec![0, 2, 3, 4, 1, 5]);
+    }
+
+    #[test]
+    fn test_three_opt() {
+        let reconnect = |reconnection| {
+            let mut route = Route::new(vec![0, 1, 2, 3, 4, 5, 6]);
+            three_opt(&mut route, 1, 3, 5, reconnection);
+            route.sequence
+        };
+        assert_eq!(reconnect(ThreeOpt::ReverseBoth), vec![0, 2, 1, 5, 4, 3, 6]);
+        assert_eq!(reconnect(ThreeOpt::ReverseFirst), vec![0, 3, 4, 5, 2, 1, 6]);
+        assert_eq!(
+            reconnect(ThreeOpt::ReverseSecond),
+            vec![0, 5, 4, 3, 1, 2, 6]
+        );
+    }
+
+    #[test]
+    fn test_relocate() {
+        let mut route1 = Route::new(vec![0, 1, 2]);
+        let mut route2 = Route::new(vec![3, 4]);
+        relocate(&mut route1, &mut route2, 1, 2);
+        assert_eq!(route1.sequence, vec![0, 2]);
+        assert_eq!(route2.sequence, vec![3, 4, 1]);
+    }
+
+    #[test]
+    fn test_exchange() {
+        let mut route1 = Route::new(vec![0, 1, 2]);
+        let mut route2 = Route::new(vec![3, 4]);
+        exchange(&mut route1, &mut route2, 0, 1);
+        assert_eq!(route1.sequence, vec![4, 1, 2]);
+        assert_eq!(route2.sequence, vec![3, 0]);
+    }
+
+    #[test]
+    fn test_cross() {
+        let mut route1 = Route::new(vec![0, 1, 2]);
+        let mut route2 = Route::new(vec![3, 4]);
+        cross(&mut route1, &mut route2, 1, 2);
+        assert_eq!(route1.sequence, vec![0]);
+        assert_eq!(route2.sequence, vec![3, 4, 1, 2]);
+    }
+
+    #[test]
+    fn test_remove_insert() {
+        let mut route = Route::new(vec![0, 1, 2]);
+        remove(&mut route, 1);
+        assert_eq!(route.sequence, vec![0, 2]);
+        insert(&mut route, 2, 1);
+        assert_eq!(route.sequence, vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn test_double_bridge() {
+        let mut route = Route::new(vec![0, 1, 2, 3, 4, 5, 6]);
+        double_bridge(&mut route, 1, 3, 6);
+        assert_eq!(route.sequence, vec![0, 3, 4, 5, 1, 2, 6]);
+    }
 }
@@ -0,0 +1,286 @@
+use crate::penalizer::Objective;
+
+/// The number of components.
+pub const COMPONENTS: usize = Component::ALL.len();
+
+/// The values of all components, ordered as `Component::ALL`.
+pub type Components = [f64; COMPONENTS];
+
+/// A quantity the solver can minimize, in the units of the time report:
+/// the number of job splits or violated pairs, seconds, units of load, the sum of priorities or costs, or the unit of the distance matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    /// The number of jobs split against their split policy.
+    SplitViolations,
+    /// The number of precedences between jobs that are violated.
+    PrecedenceViolations,
+    /// The number of pickup and delivery pairs that are not served by the same route, pickup first.
+    PickupDeliveryViolations,
+    /// The total amount by which the loads exceed the capacity of the vehicles.
+    Overload,
+    /// Time beyond the planning horizon or the maximum duration of the routes.
+    Overtime,
+    /// The number of times a job is interrupted and continued later.
+    JobSplits,
+    /// Time by which jobs end after their time windows close.
+    Lateness,
+    /// Time beyond the planning horizon or the maximum duration of the routes, if the limits are soft.
+    Excess,
+    /// The total priority of the optional jobs that are dropped.
+    DroppedPriority,
+    /// The total cost of missing the preferred time windows.
+    SoftWindowCost,
+    /// Time spent driving between locations.
+    TravelingTime,
+    /// Time from the start to the end of the routes.
+    Duration,
+    /// Time spent waiting for time windows or operation times to open.
+    WaitingTime,
+    /// The total distance of the routes.
+    Distance,
+}
+
+impl Component {
+    /// The components that measure violated constraints.
+    /// Every objective minimizes them first, each as a level of its own, in this order.
+    pub const HARD: &'static [Component] = &[
+        Component::SplitViolations,
+        Component::PrecedenceViolations,
+        Component::PickupDeliveryViolations,
+        Component::Overload,
+        Component::Overtime,
+    ];
+
+    /// All components, in the order the default objective minimizes them.
+    pub const ALL: &'static [Component] = &[
+        Component::SplitViolations,
+        Component::PrecedenceViolations,
+        Component::PickupDeliveryViolations,
+        Component::Overload,
+        Component::Overtime,
+        Component::JobSplits,
+        Component::Lateness,
+        Component::Excess,
+        Component::DroppedPriority,
+        Component::SoftWindowCost,
+        Component::TravelingTime,
+        Component::Duration,
+        Component::WaitingTime,
+        Component::Distance,
+    ];
+
+    /// The name of the component, as in the reports and the raw input.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Component::SplitViolations => "split_violations",
+            Component::PrecedenceViolations => "precedence_violations",
+            Component::PickupDeliveryViolations => "pickup_delivery_violations",
+            Component::Overload => "overload",
+            Component::Overtime => "overtime",
+            Component::JobSplits => "job_splits",
+            Component::Lateness => "lateness",
+            Component::Excess => "excess",
+            Component::DroppedPriority => "dropped_priority",
+            Component::SoftWindowCost => "soft_window_cost",
+            Component::TravelingTime => "traveling_time",
+            Component::Duration => "duration",
+            Component::WaitingTime => "waiting_time",
+            Component::Distance => "distance",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Component> {
+        Component::ALL
+            .iter()
+            .copied()
+            .find(|component| component.name() == name)
+    }
+
+    /// Whether the component measures a violated constraint.
+    pub fn is_hard(&self) -> bool {
+        Component::HARD.contains(self)
+    }
+
+    /// The position of the component in `Component::ALL`, which is its level in the default objective.
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// The values of the given components, all others being zero.
+    pub fn values(values: &[(Component, f64)]) -> Components {
+        let mut components = [0.0; COMPONENTS];
+        for &(component, value) in values {
+            components[component.index()] = value;
+        }
+        components
+    }
+}
+
+/// Solutions are compared lexicographically by the levels of the objective,
+/// each of which is a weighted sum of components.
+/// With a single level, components can be traded against each other,
+/// with one component per level, they are strictly ranked.
+/// The hard components always come first, so the levels only rank the soft ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectiveFunction {
+    levels: Vec<Vec<(Component, f64)>>,
+}
+
+impl ObjectiveFunction {
+    /// At most as many levels as there are soft components are supported.
+    pub const MAX_LEVELS: usize = COMPONENTS - Component::HARD.len();
+
+    pub fn new(levels: Vec<Vec<(Component, f64)>>) -> ObjectiveFunction {
+        assert!(levels.len() <= Self::MAX_LEVELS);
+        assert!(levels
+            .iter()
+            .flatten()
+            .all(|(component, _)| !component.is_hard()));
+        ObjectiveFunction { levels }
+    }
+
+    /// The levels of the soft components, which follow those of the hard ones.
+    pub fn levels(&self) -> &[Vec<(Component, f64)>] {
+        &self.levels
+    }
+
+    /// The number of levels, including one for each hard component.
+    pub fn level_count(&self) -> usize {
+        Component::HARD.len() + self.levels.len()
+    }
+
+    /// The value of each level for the given values of the components.
+    /// Unused levels are zero.
+    pub fn evaluate(&self, components: &Components) -> Objective {
+        let mut objective = [0.0; COMPONENTS];
+        let (hard, soft) = objective.split_at_mut(Component::HARD.len());
+        for (value, component) in hard.iter_mut().zip(Component::HARD) {
+            *value = components[component.index()];
+        }
+        for (value, level) in soft.iter_mut().zip(&self.levels) {
+            *value = level
+                .iter()
+                .map(|(component, weight)| weight * components[component.index()])
+                .sum();
+        }
+        objective
+    }
+
+    /// The component that makes the first values worse than the second ones:
+    /// in the first level that differs, the one with the largest weighted increase.
+    /// None if the first values are not worse.
+    pub fn worsened(&self, components: &Components, other: &Components) -> Option<Component> {
+        let first = self.evaluate(components);
+        let second = self.evaluate(other);
+        let level = first.iter().zip(&second).position(|(a, b)| a != b)?;
+        if first[level] < second[level] {
+            return None;
+        }
+        if level < Component::HARD.len() {
+            return Some(Component::HARD[level]);
+        }
+        self.levels[level - Component::HARD.len()]
+            .iter()
+            .map(|&(component, weight)| {
+                let increase = weight * (components[component.index()] - other[component.index()]);
+                (component, increase)
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(component, _)| component)
+    }
+}
+
+impl Default for ObjectiveFunction {
+    /// Each component as a level of its own, in the order of `Component::ALL`.
+    fn default() -> Self {
+        ObjectiveFunction::new(
+            Component::ALL
+                .iter()
+                .filter(|component| !component.is_hard())
+                .map(|&component| vec![(component, 1.0)])
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let components = Component::values(&[
+            (Component::JobSplits, 1.0),
+            (Component::Lateness, 600.0),
+            (Component::TravelingTime, 3600.0),
+            (Component::Duration, 7200.0),
+            (Component::Distance, 50000.0),
+        ]);
+        assert_eq!(
+            ObjectiveFunction::default().evaluate(&components),
+            components
+        );
+        // 10 minutes of lateness are worth 50 km of driving
+        let weighted = ObjectiveFunction::new(vec![
+            vec![(Component::JobSplits, 1.0)],
+            vec![
+                (Component::Lateness, 1.0 / 600.0),
+                (Component::Distance, 1.0 / 50000.0),
+            ],
+        ]);
+        let objective = weighted.evaluate(&components);
+        assert_eq!(objective[5..7], [1.0, 2.0]);
+        assert!(objective[..5].iter().all(|&level| level == 0.0));
+        assert!(objective[7..].iter().all(|&level| level == 0.0));
+        assert_eq!(
+            Component::from_name("waiting_time"),
+            Some(Component::WaitingTime)
+        );
+        assert_eq!(Component::from_name("waiting"), None);
+    }
+
+    #[test]
+    fn test_worsened() {
+        // the schedule of a route with ten minutes of lateness, but for the given changes
+        let components = |changes: &[(Component, f64)]| {
+            let mut values = vec![
+                (Component::Lateness, 600.0),
+                (Component::TravelingTime, 3600.0),
+                (Component::Duration, 7200.0),
+                (Component::Distance, 1000.0),
+            ];
+            values.extend_from_slice(changes);
+            Component::values(&values)
+        };
+        // visiting a job with a priority of 10 makes a job late by ten minutes
+        let visited = components(&[(Component::Lateness, 1200.0), (Component::Distance, 1100.0)]);
+        let dropped = components(&[(Component::DroppedPriority, 10.0)]);
+        let objective = ObjectiveFunction::default();
+        assert_eq!(
+            objective.worsened(&visited, &dropped),
+            Some(Component::Lateness)
+        );
+        assert_eq!(objective.worsened(&dropped, &visited), None);
+        assert_eq!(objective.worsened(&components(&[]), &components(&[])), None);
+        // a minute of lateness is worth 100 units of distance and a priority of 1
+        let dropped = components(&[(Component::DroppedPriority, 1.0)]);
+        let weighted = ObjectiveFunction::new(vec![vec![
+            (Component::Lateness, 1.0 / 60.0),
+            (Component::DroppedPriority, 1.0),
+            (Component::Distance, 1.0 / 100.0),
+        ]]);
+        let visited = components(&[(Component::Lateness, 660.0), (Component::Distance, 1500.0)]);
+        assert_eq!(
+            weighted.worsened(&visited, &dropped),
+            Some(Component::Distance)
+        );
+        // no amount of distance is worth overloading a vehicle
+        let overloaded = components(&[(Component::Overload, 1.0), (Component::Distance, 100.0)]);
+        assert_eq!(
+            weighted.worsened(&overloaded, &components(&[])),
+            Some(Component::Overload)
+        );
+        assert_eq!(weighted.worsened(&components(&[]), &overloaded), None);
+        assert_eq!(weighted.level_count(), Component::HARD.len() + 1);
+    }
+}
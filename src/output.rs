@@ -1,17 +1,149 @@
 use crate::{
-    penalties::time::time_output::{Complete, TimeOutput},
+    objective::Component,
+    penalties::{
+        capacity::capacity_output::CapacityOutput,
+        distance::DistanceCache,
+        time::{
+            precedences::PrecedenceViolation,
+            time_output::{Complete, TimeOutput},
+            ScheduleState,
+        },
+    },
     route::Route,
 };
 
 pub struct Output {
-    pub solution: Solution,
+    pub solution: MultiSolution,
+    /// The value of each level of the objective function for the solution.
+    pub objective: Vec<f64>,
+    /// The optional jobs that are not part of any route.
+    pub dropped: Vec<DroppedJob>,
+    /// The precedences between jobs that the solution violates.
+    pub precedence_violations: Vec<PrecedenceViolation>,
+    /// Whether the solution is known to be optimal, since it was found exactly.
+    pub optimal: bool,
     pub iterations: u64,
     pub time_taken: chrono::Duration,
 }
 
+/// An optional job that the solver dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DroppedJob {
+    pub location: usize,
+    pub priority: f64,
+    /// The component of the objective that visiting the job would worsen the most,
+    /// e.g. lateness if every route would miss a time window.
+    pub reason: Option<Component>,
+}
+
 #[derive(Clone)]
 pub struct Solution {
     pub route: Route,
     pub distance: u64,
     pub time_report: Option<TimeOutput<Complete>>,
+    pub capacity_report: Option<CapacityOutput>,
+    /// Caches to evaluate local moves on the route without starting from scratch.
+    pub distance_cache: DistanceCache,
+    pub schedule_states: Vec<ScheduleState>,
+}
+
+/// The index of a route with its new distance, time and capacity report.
+pub type Replacement<'a> = (
+    usize,
+    u64,
+    Option<&'a TimeOutput<Complete>>,
+    Option<&'a CapacityOutput>,
+);
+
+/// A solution consisting of one route per vehicle,
+/// together with the distance, time and capacity report over all routes.
+#[derive(Clone)]
+pub struct MultiSolution {
+    pub routes: Vec<Solution>,
+    pub distance: u64,
+    pub time_report: Option<TimeOutput<Complete>>,
+    pub capacity_report: Option<CapacityOutput>,
+    /// The optional jobs that are not part of any route.
+    pub dropped: Vec<usize>,
+}
+
+impl MultiSolution {
+    pub fn new(routes: Vec<Solution>) -> MultiSolution {
+        let distance = routes.iter().map(|solution| solution.distance).sum();
+        let time_report = combined_time_report(&routes);
+        let capacity_report = combined_capacity_report(&routes);
+        MultiSolution {
+            routes,
+            distance,
+            time_report,
+            capacity_report,
+            dropped: vec![],
+        }
+    }
+
+    /// Total distance, time and capacity report if the routes at the given indices
+    /// had the given distances, time and capacity reports instead.
+    /// The reports only hold the totals, without schedules, stops or loads.
+    pub fn totals_with(
+        &self,
+        replacements: &[Replacement],
+    ) -> (u64, Option<TimeOutput<Complete>>, Option<CapacityOutput>) {
+        let mut total_distance = self.distance;
+        let mut time_reports: Option<Vec<&TimeOutput<Complete>>> = self
+            .routes
+            .iter()
+            .map(|solution| solution.time_report.as_ref())
+            .collect();
+        let mut capacity_reports: Option<Vec<&CapacityOutput>> = self
+            .routes
+            .iter()
+            .map(|solution| solution.capacity_report.as_ref())
+            .collect();
+        for &(index, distance, time_report, capacity_report) in replacements {
+            total_distance = total_distance - self.routes[index].distance + distance;
+            if let (Some(time_reports), Some(time_report)) = (&mut time_reports, time_report) {
+                time_reports[index] = time_report;
+            }
+            if let (Some(capacity_reports), Some(capacity_report)) =
+                (&mut capacity_reports, capacity_report)
+            {
+                capacity_reports[index] = capacity_report;
+            }
+        }
+        let total_time_report =
+            time_reports.and_then(|reports| TimeOutput::combine_totals(&reports));
+        let total_capacity_report =
+            capacity_reports.and_then(|reports| CapacityOutput::combine_totals(&reports));
+        (total_distance, total_time_report, total_capacity_report)
+    }
+
+    pub fn set_route(&mut self, index: usize, solution: Solution) {
+        self.distance = self.distance - self.routes[index].distance + solution.distance;
+        self.routes[index] = solution;
+        self.time_report = combined_time_report(&self.routes);
+        self.capacity_report = combined_capacity_report(&self.routes);
+    }
+
+    pub fn sequences(&self) -> Vec<Vec<usize>> {
+        self.routes
+            .iter()
+            .map(|solution| solution.route.sequence.clone())
+            .collect()
+    }
+}
+
+fn combined_time_report(routes: &[Solution]) -> Option<TimeOutput<Complete>> {
+    routes
+        .iter()
+        .map(|solution| solution.time_report.as_ref())
+        .collect::<Option<Vec<&TimeOutput<Complete>>>>()
+        .and_then(|reports| TimeOutput::combine(&reports))
+}
+
+fn combined_capacity_report(routes: &[Solution]) -> Option<CapacityOutput> {
+    routes
+        .iter()
+        .map(|solution| solution.capacity_report.as_ref())
+        .collect::<Option<Vec<&CapacityOutput>>>()
+        .and_then(|reports| CapacityOutput::combine(&reports))
 }
@@ -1,6 +1,8 @@
 use crate::{
-    output::Solution,
+    objective::{Component, Components, ObjectiveFunction, COMPONENTS},
+    output::{MultiSolution, Solution},
     penalties::{
+        capacity::{capacity_output::CapacityOutput, CapacityPenalizer},
         distance::DistancePenalizer,
         time::{
             time_output::{Complete, TimeOutput},
@@ -10,9 +12,18 @@ use crate::{
     route::Route,
 };
 
+/// The values of the levels of the objective function.
+/// Solutions are compared lexicographically by them.
+pub type Objective = [f64; COMPONENTS];
+
 pub struct Penalizer {
     pub distance_penalizer: DistancePenalizer,
     pub time_penalizer: Option<TimePenalizer>,
+    pub capacity_penalizer: Option<CapacityPenalizer>,
+    pub objective_function: ObjectiveFunction,
+    /// The priority of each optional job, None for jobs that have to be visited.
+    /// Without any, all jobs have to be visited.
+    pub priorities: Vec<Option<f64>>,
 }
 
 impl Penalizer {
@@ -23,65 +34,241 @@ impl Penalizer {
         Penalizer {
             distance_penalizer,
             time_penalizer,
+            capacity_penalizer: None,
+            objective_function: ObjectiveFunction::default(),
+            priorities: vec![],
         }
     }
 
     pub fn penalize(&self, route: Route, build_schedule: bool) -> Solution {
         let distance = self.distance_penalizer.penalize(&route);
-        let time_report = self.time(&route, build_schedule);
+        let distance_cache = self.distance_penalizer.cache(&route);
+        let (time_report, schedule_states) = match (&self.time_penalizer, build_schedule) {
+            (Some(time_penalizer), false) => {
+                let (time_report, states) = time_penalizer.penalize_with_states(&route);
+                (Some(time_report), states)
+            }
+            _ => (self.time(&route, build_schedule), vec![]),
+        };
+        let capacity_report = self.capacity(&route, build_schedule);
         Solution {
             route,
             distance,
             time_report,
+            capacity_report,
+            distance_cache,
+            schedule_states,
+        }
+    }
+
+    /// Evaluates the route of `solution` after applying `local_move`, which changes
+    /// the route only from position `from` on and the distance by `distance_delta`.
+    /// The route is only built if the time or the capacity penalizer needs it.
+    pub fn penalize_move(
+        &self,
+        solution: &Solution,
+        local_move: &mut dyn FnMut(&mut Route),
+        distance_delta: i64,
+        from: usize,
+    ) -> (u64, Option<TimeOutput<Complete>>, Option<CapacityOutput>) {
+        let distance = solution
+            .distance
+            .checked_add_signed(distance_delta)
+            .unwrap();
+        if self.time_penalizer.is_none() && self.capacity_penalizer.is_none() {
+            return (distance, None, None);
         }
+        let mut route = solution.route.clone();
+        local_move(&mut route);
+        self.penalize_changed(solution, &route, distance, from)
+    }
+
+    /// Like `penalize_move` for a move between the routes of two solutions,
+    /// which changes the first route from position `from[0]` on and its distance by
+    /// `distance_deltas[0]`, and the second one likewise.
+    pub fn penalize_inter_move(
+        &self,
+        solutions: [&Solution; 2],
+        local_move: &mut dyn FnMut(&mut Route, &mut Route),
+        distance_deltas: [i64; 2],
+        from: [usize; 2],
+    ) -> [(u64, Option<TimeOutput<Complete>>, Option<CapacityOutput>); 2] {
+        let distances = [0, 1].map(|k| {
+            solutions[k]
+                .distance
+                .checked_add_signed(distance_deltas[k])
+                .unwrap()
+        });
+        if self.time_penalizer.is_none() && self.capacity_penalizer.is_none() {
+            return distances.map(|distance| (distance, None, None));
+        }
+        let mut route1 = solutions[0].route.clone();
+        let mut route2 = solutions[1].route.clone();
+        local_move(&mut route1, &mut route2);
+        [
+            self.penalize_changed(solutions[0], &route1, distances[0], from[0]),
+            self.penalize_changed(solutions[1], &route2, distances[1], from[1]),
+        ]
+    }
+
+    /// Evaluates `route` with the given distance, which only differs from the route of `solution`
+    /// from position `from` on, without the caches of a `Solution`,
+    /// which are only needed once a move is accepted.
+    fn penalize_changed(
+        &self,
+        solution: &Solution,
+        route: &Route,
+        distance: u64,
+        from: usize,
+    ) -> (u64, Option<TimeOutput<Complete>>, Option<CapacityOutput>) {
+        let time_report = self.time_penalizer.as_ref().map(|time_penalizer| {
+            time_penalizer.penalize_from(route, &solution.schedule_states, from)
+        });
+        (distance, time_report, self.capacity(route, false))
+    }
+
+    /// Penalizes every route on its own and sums up the penalties over all routes.
+    pub fn penalize_routes(&self, routes: Vec<Route>, build_schedule: bool) -> MultiSolution {
+        MultiSolution::new(
+            routes
+                .into_iter()
+                .map(|route| self.penalize(route, build_schedule))
+                .collect(),
+        )
     }
 
     pub fn is_better(&self, sol1: &Solution, sol2: &Solution) -> bool {
-        match &self.time_penalizer {
-            None => sol1.distance < sol2.distance,
-            Some(_) => {
-                let time_report1 = sol1.time_report.as_ref().unwrap();
-                let time_report2 = sol2.time_report.as_ref().unwrap();
-                if time_report1.job_splits < time_report2.job_splits {
-                    return true;
-                }
-                if time_report1.job_splits > time_report2.job_splits {
-                    return false;
-                }
-                if time_report1.lateness < time_report2.lateness {
-                    return true;
-                }
-                if time_report1.lateness > time_report2.lateness {
-                    return false;
-                }
-                if time_report1.traveling_time < time_report2.traveling_time {
-                    return true;
-                }
-                if time_report1.traveling_time > time_report2.traveling_time {
-                    return false;
-                }
-                if time_report1.duration < time_report2.duration {
-                    return true;
-                }
-                if time_report1.duration > time_report2.duration {
-                    return false;
-                }
-                if time_report1.waiting_time < time_report2.waiting_time {
-                    return true;
-                }
-                if time_report1.waiting_time > time_report2.waiting_time {
-                    return false;
-                }
-                sol1.distance < sol2.distance
-            }
+        self.is_better_report(
+            sol1.distance,
+            sol1.time_report.as_ref(),
+            sol1.capacity_report.as_ref(),
+            &[],
+            sol2.distance,
+            sol2.time_report.as_ref(),
+            sol2.capacity_report.as_ref(),
+            &[],
+        )
+    }
+
+    pub fn is_better_multi(&self, sol1: &MultiSolution, sol2: &MultiSolution) -> bool {
+        self.objective_multi(sol1) < self.objective_multi(sol2)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn is_better_report(
+        &self,
+        distance1: u64,
+        time_report1: Option<&TimeOutput<Complete>>,
+        capacity_report1: Option<&CapacityOutput>,
+        dropped1: &[usize],
+        distance2: u64,
+        time_report2: Option<&TimeOutput<Complete>>,
+        capacity_report2: Option<&CapacityOutput>,
+        dropped2: &[usize],
+    ) -> bool {
+        self.objective(distance1, time_report1, capacity_report1, dropped1)
+            < self.objective(distance2, time_report2, capacity_report2, dropped2)
+    }
+
+    /// The values of all components, the time components are zero without a time penalizer,
+    /// and the capacity components without a capacity penalizer.
+    pub fn components(
+        &self,
+        distance: u64,
+        time_report: Option<&TimeOutput<Complete>>,
+        capacity_report: Option<&CapacityOutput>,
+        dropped: &[usize],
+    ) -> Components {
+        let mut values = vec![
+            (Component::DroppedPriority, self.dropped_priority(dropped)),
+            (Component::Distance, distance as f64),
+        ];
+        if let Some(capacity_report) = capacity_report {
+            values.extend([
+                (
+                    Component::PickupDeliveryViolations,
+                    capacity_report.violated_pairs.len() as f64,
+                ),
+                (Component::Overload, capacity_report.overload as f64),
+            ]);
+        }
+        if let (Some(time_penalizer), Some(time_report)) = (&self.time_penalizer, time_report) {
+            let seconds = |duration: chrono::Duration| duration.num_seconds() as f64;
+            values.extend([
+                (
+                    Component::SplitViolations,
+                    time_report.split_violations as f64,
+                ),
+                (
+                    Component::PrecedenceViolations,
+                    time_penalizer.precedence_violations(time_report).len() as f64,
+                ),
+                (Component::Overtime, seconds(time_report.overtime)),
+                (Component::JobSplits, time_report.job_splits as f64),
+                (Component::Lateness, seconds(time_report.lateness)),
+                (Component::Excess, seconds(time_report.excess)),
+                (Component::SoftWindowCost, time_report.soft_window_cost),
+                (
+                    Component::TravelingTime,
+                    seconds(time_report.traveling_time),
+                ),
+                (Component::Duration, seconds(time_report.duration)),
+                (Component::WaitingTime, seconds(time_report.waiting_time)),
+            ]);
         }
+        Component::values(&values)
+    }
+
+    /// The levels of the objective function.
+    pub fn objective(
+        &self,
+        distance: u64,
+        time_report: Option<&TimeOutput<Complete>>,
+        capacity_report: Option<&CapacityOutput>,
+        dropped: &[usize],
+    ) -> Objective {
+        self.objective_function.evaluate(&self.components(
+            distance,
+            time_report,
+            capacity_report,
+            dropped,
+        ))
+    }
+
+    /// The objective of all routes together.
+    pub fn objective_multi(&self, solution: &MultiSolution) -> Objective {
+        self.objective(
+            solution.distance,
+            solution.time_report.as_ref(),
+            solution.capacity_report.as_ref(),
+            &solution.dropped,
+        )
+    }
+
+    /// Whether the job at the location may be dropped from the routes.
+    pub fn is_optional(&self, location: usize) -> bool {
+        matches!(self.priorities.get(location), Some(Some(_)))
+    }
+
+    /// The total priority of the given dropped jobs.
+    pub fn dropped_priority(&self, dropped: &[usize]) -> f64 {
+        dropped
+            .iter()
+            .filter_map(|&location| self.priorities[location])
+            // an empty sum of floats is -0.0, which would show up in the reports
+            .fold(0.0, |total, priority| total + priority)
     }
 
     pub fn time(&self, route: &Route, build_schedule: bool) -> Option<TimeOutput<Complete>> {
-        match &self.time_penalizer {
-            None => None,
-            Some(time_penalizer) => Some(time_penalizer.penalize(&route, build_schedule)),
-        }
+        self.time_penalizer
+            .as_ref()
+            .map(|time_penalizer| time_penalizer.penalize(route, build_schedule))
+    }
+
+    pub fn capacity(&self, route: &Route, build_loads: bool) -> Option<CapacityOutput> {
+        self.capacity_penalizer
+            .as_ref()
+            .map(|capacity_penalizer| capacity_penalizer.penalize(route, build_loads))
     }
 }
 
@@ -93,18 +280,20 @@ mod tests {
         distance::DistanceMatrix,
         time::{
             operation_times::OperationTimes,
-            time_input::TimeInput,
-            time_output::Event,
+            soft_windows::{CostFunction, JobPenalty, SoftWindow},
+            time_input::{Limits, SplitPolicy, Start, TimeInput},
+            time_output::{Complete, Event, Stop, TimeOutput},
             time_windows::{TimeWindow, TimeWindows},
         },
     };
+    use crate::route::Vehicle;
     use chrono::{NaiveTime, TimeZone, Utc};
 
     #[test]
     fn test_penalizer() {
         let distance_matrix =
             DistanceMatrix::new(vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]]);
-        let time_input = Some(TimeInput {
+        let time_input = TimeInput {
             job_durations: vec![chrono::Duration::hours(3); 3],
             time_windows: vec![
                 TimeWindows::new(vec![
@@ -162,9 +351,10 @@ mod tests {
             ],
             travel_duration_until_break: None,
             break_duration: None,
-        });
+            ..TimeInput::default()
+        };
         let distance_penalizer = DistancePenalizer::new(distance_matrix);
-        let time_penalizer = TimePenalizer::new(time_input.unwrap());
+        let time_penalizer = TimePenalizer::new(time_input);
         let penalizer = Penalizer::new(distance_penalizer, Some(time_penalizer));
         let route = Route::new(vec![0, 1, 2]);
         let solution = penalizer.penalize(route, true);
@@ -258,11 +448,708 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_penalizer_breaks() {
+        let distance_matrix = DistanceMatrix::new(vec![vec![0, 1], vec![1, 0]]);
+        let time_window = TimeWindow::new(
+            Utc.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 1, 5, 8, 0, 0).unwrap(),
+        );
+        let time_input = TimeInput {
+            job_durations: vec![chrono::Duration::hours(1); 2],
+            time_windows: vec![
+                TimeWindows::new(vec![time_window.clone()]),
+                TimeWindows::new(vec![time_window]),
+            ],
+            operation_times: Some(OperationTimes::new(
+                NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+                None,
+            )),
+            duration_matrix: vec![
+                vec![chrono::Duration::hours(0), chrono::Duration::hours(5)],
+                vec![chrono::Duration::hours(5), chrono::Duration::hours(0)],
+            ],
+            travel_duration_until_break: Some(chrono::Duration::hours(2)),
+            break_duration: Some(chrono::Duration::minutes(30)),
+            ..TimeInput::default()
+        };
+        let distance_penalizer = DistancePenalizer::new(distance_matrix);
+        let time_penalizer = TimePenalizer::new(time_input);
+        let penalizer = Penalizer::new(distance_penalizer, Some(time_penalizer));
+        let solution = penalizer.penalize(Route::new(vec![0, 1]), true);
+        let time_report = solution.time_report.unwrap();
+        assert_eq!(time_report.traveling_time, chrono::Duration::hours(10));
+        assert_eq!(time_report.break_time, chrono::Duration::hours(2));
+        assert_eq!(time_report.duration, chrono::Duration::hours(26));
+        assert_eq!(
+            time_report.end_time,
+            Utc.with_ymd_and_hms(2021, 1, 2, 10, 0, 0).unwrap()
+        );
+        assert_eq!(time_report.schedule.len(), 13);
+        // no more than two hours of travel without a break
+        assert_eq!(
+            time_report.schedule[2],
+            Event::Break(TimeWindow::new(
+                Utc.with_ymd_and_hms(2021, 1, 1, 11, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2021, 1, 1, 11, 30, 0).unwrap(),
+            ))
+        );
+        // working does not count as a break
+        assert_eq!(
+            time_report.schedule[8],
+            Event::Break(TimeWindow::new(
+                Utc.with_ymd_and_hms(2021, 1, 1, 17, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2021, 1, 1, 17, 30, 0).unwrap(),
+            ))
+        );
+        // the remaining travel continues on the next day
+        assert_eq!(
+            time_report.schedule[12],
+            Event::Travel(TimeWindow::new(
+                Utc.with_ymd_and_hms(2021, 1, 2, 8, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2021, 1, 2, 10, 0, 0).unwrap(),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_penalizer_limits() {
+        let time_window = TimeWindow::new(
+            Utc.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2021, 1, 5, 8, 0, 0).unwrap(),
+        );
+        let penalizer = |limits| {
+            let time_input = TimeInput {
+                job_durations: vec![chrono::Duration::hours(1); 2],
+                time_windows: vec![
+                    TimeWindows::new(vec![time_window.clone()]),
+                    TimeWindows::new(vec![time_window.clone()]),
+                ],
+                operation_times: Some(OperationTimes::new(
+                    NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                    NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+                    None,
+                )),
+                duration_matrix: vec![
+                    vec![chrono::Duration::hours(0), chrono::Duration::hours(5)],
+                    vec![chrono::Duration::hours(5), chrono::Duration::hours(0)],
+                ],
+                travel_duration_until_break: None,
+                break_duration: None,
+                limits,
+                ..TimeInput::default()
+            };
+            Penalizer::new(
+                DistancePenalizer::new(DistanceMatrix::new(vec![vec![0, 1], vec![1, 0]])),
+                Some(TimePenalizer::new(time_input)),
+            )
+        };
+        // the tour ends at 20:00, after 12 hours
+        let limits = Limits {
+            horizon_end: Some(Utc.with_ymd_and_hms(2021, 1, 1, 18, 0, 0).unwrap()),
+            max_duration: Some(chrono::Duration::hours(10)),
+            soft: false,
+        };
+        let time_report = penalizer(limits)
+            .penalize(Route::new(vec![0, 1]), false)
+            .time_report
+            .unwrap();
+        assert_eq!(time_report.duration, chrono::Duration::hours(12));
+        assert_eq!(time_report.overtime, chrono::Duration::hours(4));
+        assert_eq!(time_report.excess, chrono::Duration::zero());
+        assert_eq!(time_report.lateness, chrono::Duration::zero());
+        let time_report = penalizer(Limits {
+            soft: true,
+            ..limits
+        })
+        .penalize(Route::new(vec![0, 1]), false)
+        .time_report
+        .unwrap();
+        assert_eq!(time_report.overtime, chrono::Duration::zero());
+        assert_eq!(time_report.excess, chrono::Duration::hours(4));
+        assert_eq!(time_report.lateness, chrono::Duration::zero());
+        let time_report = penalizer(Limits::default())
+            .penalize(Route::new(vec![0, 1]), false)
+            .time_report
+            .unwrap();
+        assert_eq!(time_report.overtime, chrono::Duration::zero());
+    }
+
+    #[test]
+    fn test_penalizer_start() {
+        let time_input = |start| TimeInput {
+            job_durations: vec![chrono::Duration::hours(1); 2],
+            time_windows: vec![
+                TimeWindows::new(vec![TimeWindow::new(
+                    Utc.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2021, 1, 1, 12, 0, 0).unwrap(),
+                )]),
+                TimeWindows::new(vec![TimeWindow::new(
+                    Utc.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2021, 1, 1, 18, 0, 0).unwrap(),
+                )]),
+            ],
+            operation_times: Some(OperationTimes::new(
+                NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+                None,
+            )),
+            duration_matrix: vec![
+                vec![chrono::Duration::hours(0), chrono::Duration::hours(2)],
+                vec![chrono::Duration::hours(2), chrono::Duration::hours(0)],
+            ],
+            travel_duration_until_break: None,
+            break_duration: None,
+            start,
+            ..TimeInput::default()
+        };
+        let time_report =
+            |start| TimePenalizer::new(time_input(start)).penalize(&Route::new(vec![1, 0]), false);
+        // without a start time, the route starts when location 1 opens
+        let report = time_report(Start::default());
+        assert_eq!(
+            report.start_time,
+            Utc.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap()
+        );
+        assert_eq!(report.lateness, chrono::Duration::zero());
+        // the vehicle is only available at 10:00 and is late at location 0
+        let report = time_report(Start {
+            time: Some(Utc.with_ymd_and_hms(2021, 1, 1, 10, 0, 0).unwrap()),
+            ..Start::default()
+        });
+        assert_eq!(
+            report.start_time,
+            Utc.with_ymd_and_hms(2021, 1, 1, 10, 0, 0).unwrap()
+        );
+        assert_eq!(report.lateness, chrono::Duration::hours(2));
+        // available at 06:00, it waits for location 1 to open
+        let report = time_report(Start {
+            time: Some(Utc.with_ymd_and_hms(2021, 1, 1, 6, 0, 0).unwrap()),
+            ..Start::default()
+        });
+        assert_eq!(report.waiting_time, chrono::Duration::hours(2));
+        // but not if it may only leave at 09:00
+        let report = time_report(Start {
+            time: Some(Utc.with_ymd_and_hms(2021, 1, 1, 6, 0, 0).unwrap()),
+            earliest_departure: NaiveTime::from_hms_opt(9, 0, 0),
+            ..Start::default()
+        });
+        assert_eq!(
+            report.start_time,
+            Utc.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap()
+        );
+        assert_eq!(report.waiting_time, chrono::Duration::zero());
+        assert_eq!(report.lateness, chrono::Duration::hours(1));
+        // from the depot at location 0, the vehicle leaves two hours before location 1 opens,
+        // but not before the depot opens
+        let from_depot = |depot_opening| {
+            let mut time_input = time_input(Start::default());
+            time_input.time_windows = vec![
+                TimeWindows::new(vec![TimeWindow::new(
+                    Utc.with_ymd_and_hms(2021, 1, 1, depot_opening, 0, 0)
+                        .unwrap(),
+                    Utc.with_ymd_and_hms(2021, 1, 1, 20, 0, 0).unwrap(),
+                )]),
+                TimeWindows::new(vec![TimeWindow::new(
+                    Utc.with_ymd_and_hms(2021, 1, 1, 10, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2021, 1, 1, 12, 0, 0).unwrap(),
+                )]),
+            ];
+            TimePenalizer::new(time_input)
+                .penalize(&Route::with_vehicle(vec![1], Vehicle::new(0, 0)), false)
+        };
+        let report = from_depot(6);
+        assert_eq!(
+            report.start_time,
+            Utc.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap()
+        );
+        assert_eq!(report.waiting_time, chrono::Duration::zero());
+        assert_eq!(report.lateness, chrono::Duration::zero());
+        let report = from_depot(9);
+        assert_eq!(
+            report.start_time,
+            Utc.with_ymd_and_hms(2021, 1, 1, 9, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_penalizer_unconstrained() {
+        // location 1 can be visited at any time, within the operation times
+        let time_input = TimeInput {
+            job_durations: vec![chrono::Duration::hours(1), chrono::Duration::hours(10)],
+            time_windows: vec![
+                TimeWindows::new(vec![TimeWindow::new(
+                    Utc.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2021, 1, 1, 12, 0, 0).unwrap(),
+                )]),
+                TimeWindows::new(vec![]),
+            ],
+            operation_times: Some(OperationTimes::new(
+                NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+                None,
+            )),
+            duration_matrix: vec![
+                vec![chrono::Duration::hours(0), chrono::Duration::hours(2)],
+                vec![chrono::Duration::hours(2), chrono::Duration::hours(0)],
+            ],
+            travel_duration_until_break: None,
+            break_duration: None,
+            ..TimeInput::default()
+        };
+        let penalizer = TimePenalizer::new(time_input);
+        // the long job is split over two days, but it is never late
+        let report = penalizer.penalize(&Route::new(vec![0, 1]), false);
+        assert_eq!(report.job_splits, 1);
+        assert_eq!(report.lateness, chrono::Duration::zero());
+        assert_eq!(
+            report.end_time,
+            Utc.with_ymd_and_hms(2021, 1, 2, 15, 0, 0).unwrap()
+        );
+        // starting with it, the route starts when location 0 opens
+        let report = penalizer.penalize(&Route::new(vec![1, 0]), false);
+        assert_eq!(
+            report.start_time,
+            Utc.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap()
+        );
+        assert_eq!(report.lateness, chrono::Duration::hours(25));
+    }
+
+    #[test]
+    fn test_penalizer_soft_windows() {
+        let at = |h| Utc.with_ymd_and_hms(2021, 1, 1, h, 0, 0).unwrap();
+        let time_input = TimeInput {
+            job_durations: vec![chrono::Duration::hours(1); 2],
+            time_windows: vec![
+                TimeWindows::new(vec![TimeWindow::new(at(8), at(18))]),
+                TimeWindows::new(vec![TimeWindow::new(at(8), at(18))]),
+            ],
+            operation_times: Some(OperationTimes::new(
+                NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+                None,
+            )),
+            duration_matrix: vec![
+                vec![chrono::Duration::hours(0), chrono::Duration::hours(2)],
+                vec![chrono::Duration::hours(2), chrono::Duration::hours(0)],
+            ],
+            travel_duration_until_break: None,
+            break_duration: None,
+            soft_windows: vec![
+                Some(SoftWindow {
+                    window: TimeWindow::new(at(12), at(13)),
+                    earliness: CostFunction::linear(1.0),
+                    lateness: CostFunction::linear(2.0),
+                }),
+                Some(SoftWindow {
+                    window: TimeWindow::new(at(8), at(9)),
+                    earliness: CostFunction::linear(1.0),
+                    // half an hour late is fine
+                    lateness: CostFunction::new(vec![
+                        (chrono::Duration::minutes(30), 0.0),
+                        (chrono::Duration::hours(1), 2.0),
+                    ]),
+                }),
+            ],
+            ..TimeInput::default()
+        };
+        let penalizer = TimePenalizer::new(time_input);
+        // location 0 is done four hours early instead of waiting, location 1 ends three hours late
+        let report = penalizer.penalize(&Route::new(vec![0, 1]), true);
+        assert_eq!(report.waiting_time, chrono::Duration::zero());
+        assert_eq!(report.lateness, chrono::Duration::zero());
+        assert_eq!(report.soft_window_cost, 14.0);
+        assert_eq!(
+            report.penalties,
+            vec![
+                JobPenalty {
+                    location: 0,
+                    earliness: chrono::Duration::hours(4),
+                    lateness: chrono::Duration::zero(),
+                    cost: 4.0
+                },
+                JobPenalty {
+                    location: 1,
+                    earliness: chrono::Duration::zero(),
+                    lateness: chrono::Duration::hours(3),
+                    cost: 10.0
+                }
+            ]
+        );
+        // the other way round, only location 0 misses its preferred time window, by one hour
+        let report = penalizer.penalize(&Route::new(vec![1, 0]), true);
+        assert_eq!(report.soft_window_cost, 1.0);
+        assert_eq!(report.penalties.len(), 1);
+    }
+
+    #[test]
+    fn test_penalizer_stops() {
+        let at = |d, h| Utc.with_ymd_and_hms(2021, 1, d, h, 0, 0).unwrap();
+        let time_input = TimeInput {
+            job_durations: vec![chrono::Duration::hours(1), chrono::Duration::hours(10)],
+            time_windows: vec![
+                TimeWindows::new(vec![
+                    TimeWindow::new(at(1, 6), at(1, 7)),
+                    TimeWindow::new(at(1, 10), at(1, 12)),
+                ]),
+                TimeWindows::new(vec![TimeWindow::new(at(1, 8), at(1, 9))]),
+            ],
+            operation_times: Some(OperationTimes::new(
+                NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+                None,
+            )),
+            duration_matrix: vec![
+                vec![chrono::Duration::hours(0), chrono::Duration::hours(2)],
+                vec![chrono::Duration::hours(2), chrono::Duration::hours(0)],
+            ],
+            travel_duration_until_break: None,
+            break_duration: None,
+            limits: Limits::default(),
+            start: Start {
+                time: Some(at(1, 8)),
+                ..Start::default()
+            },
+            soft_windows: vec![],
+            split_policies: vec![],
+            precedences: vec![],
+        };
+        let penalizer = TimePenalizer::new(time_input);
+        let report = penalizer.penalize(&Route::new(vec![0, 1]), true);
+        assert_eq!(
+            report.stops,
+            vec![
+                // waiting for the second time window
+                Stop {
+                    location: 0,
+                    arrival: at(1, 8),
+                    service_start: at(1, 10),
+                    service_end: at(1, 11),
+                    departure: at(1, 11),
+                    waiting_time: chrono::Duration::hours(2),
+                    lateness: chrono::Duration::zero(),
+                    time_window: Some(1),
+                    split: false,
+                },
+                // too late for any time window, the long job is split over two days
+                Stop {
+                    location: 1,
+                    arrival: at(1, 13),
+                    service_start: at(1, 13),
+                    service_end: at(2, 15),
+                    departure: at(2, 15),
+                    waiting_time: chrono::Duration::zero(),
+                    lateness: chrono::Duration::hours(30),
+                    time_window: None,
+                    split: true,
+                },
+            ]
+        );
+        assert_eq!(report.lateness, chrono::Duration::hours(30));
+        // stops are only reported along with the schedule
+        let report = penalizer.penalize(&Route::new(vec![0, 1]), false);
+        assert!(report.stops.is_empty());
+    }
+
+    #[test]
+    fn test_penalizer_around_the_clock() {
+        let at = |d, h, m| Utc.with_ymd_and_hms(2021, 1, d, h, m, 0).unwrap();
+        let time_input = TimeInput {
+            job_durations: vec![chrono::Duration::hours(1); 2],
+            time_windows: vec![
+                TimeWindows::new(vec![TimeWindow::new(at(1, 22, 0), at(1, 23, 0))]),
+                TimeWindows::new(vec![TimeWindow::new(at(2, 1, 0), at(2, 3, 0))]),
+            ],
+            operation_times: None,
+            duration_matrix: vec![
+                vec![chrono::Duration::hours(0), chrono::Duration::hours(2)],
+                vec![chrono::Duration::hours(2), chrono::Duration::hours(0)],
+            ],
+            travel_duration_until_break: Some(chrono::Duration::hours(1)),
+            break_duration: Some(chrono::Duration::minutes(30)),
+            ..TimeInput::default()
+        };
+        let penalizer = TimePenalizer::new(time_input);
+        // the night shift travels right through midnight, only interrupted by its breaks
+        let report = penalizer.penalize(&Route::new(vec![0, 1]), true);
+        assert_eq!(report.end_time, at(2, 5, 30));
+        assert_eq!(report.break_time, chrono::Duration::minutes(90));
+        assert_eq!(report.waiting_time, chrono::Duration::zero());
+        assert_eq!(report.lateness, chrono::Duration::zero());
+        assert_eq!(report.job_splits, 0);
+        assert_eq!(
+            report.schedule[..4],
+            [
+                Event::Work(TimeWindow::new(at(1, 22, 0), at(1, 23, 0)), 0),
+                Event::Travel(TimeWindow::new(at(1, 23, 0), at(2, 0, 0))),
+                Event::Break(TimeWindow::new(at(2, 0, 0), at(2, 0, 30))),
+                Event::Travel(TimeWindow::new(at(2, 0, 30), at(2, 1, 30))),
+            ]
+        );
+        // jobs are done within their time windows, or else in one go
+        let report = penalizer.penalize(&Route::new(vec![1, 0]), true);
+        assert_eq!(report.job_splits, 0);
+        assert_eq!(report.lateness, chrono::Duration::minutes(390));
+    }
+
+    #[test]
+    fn test_penalizer_split_policies() {
+        let at = |d, h| Utc.with_ymd_and_hms(2021, 1, d, h, 0, 0).unwrap();
+        let time_input = |time_windows, start_time, split_policy| TimeInput {
+            job_durations: vec![chrono::Duration::hours(10)],
+            time_windows: vec![TimeWindows::new(time_windows)],
+            operation_times: Some(OperationTimes::new(
+                NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+                None,
+            )),
+            duration_matrix: vec![vec![chrono::Duration::zero()]],
+            travel_duration_until_break: None,
+            break_duration: None,
+            limits: Limits::default(),
+            start: Start {
+                time: start_time,
+                ..Start::default()
+            },
+            soft_windows: vec![],
+            split_policies: vec![split_policy],
+            precedences: vec![],
+        };
+        let work = |report: &TimeOutput<Complete>| {
+            report
+                .schedule
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Work(window, _) => Some((window.start, window.end)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        // arriving an hour before the end of the shift
+        let penalize = |split_policy| {
+            TimePenalizer::new(time_input(
+                vec![TimeWindow::new(at(1, 0), at(10, 0))],
+                Some(at(1, 15)),
+                split_policy,
+            ))
+            .penalize(&Route::new(vec![0]), true)
+        };
+        let report = penalize(SplitPolicy::default());
+        assert_eq!(
+            work(&report),
+            vec![
+                (at(1, 15), at(1, 16)),
+                (at(2, 8), at(2, 16)),
+                (at(3, 8), at(3, 9))
+            ]
+        );
+        assert_eq!((report.job_splits, report.split_violations), (1, 0));
+        assert!(report.is_feasible());
+        // that hour is too short for a part of the job
+        let report = penalize(SplitPolicy::Allow {
+            min_part: chrono::Duration::hours(2),
+        });
+        assert_eq!(
+            work(&report),
+            vec![(at(2, 8), at(2, 16)), (at(3, 8), at(3, 10))]
+        );
+        assert_eq!((report.job_splits, report.split_violations), (1, 0));
+        // a job that must not be split, or whose parts never fit, is split anyway
+        for split_policy in [
+            SplitPolicy::Forbid,
+            SplitPolicy::Allow {
+                min_part: chrono::Duration::hours(9),
+            },
+        ] {
+            let report = penalize(split_policy);
+            assert_eq!(work(&report).len(), 3);
+            assert_eq!((report.job_splits, report.split_violations), (0, 1));
+            assert!(!report.is_feasible());
+        }
+        // a short time window on the first day
+        let penalize = |split_policy| {
+            TimePenalizer::new(time_input(
+                vec![
+                    TimeWindow::new(at(1, 8), at(1, 12)),
+                    TimeWindow::new(at(2, 8), at(2, 20)),
+                ],
+                None,
+                split_policy,
+            ))
+            .penalize(&Route::new(vec![0]), true)
+        };
+        assert_eq!(
+            work(&penalize(SplitPolicy::default())),
+            vec![(at(1, 8), at(1, 12)), (at(2, 8), at(2, 14))]
+        );
+        // only the end of a shift may interrupt the job
+        let report = penalize(SplitPolicy::AcrossDays {
+            min_part: chrono::Duration::zero(),
+        });
+        assert_eq!(
+            work(&report),
+            vec![(at(2, 8), at(2, 16)), (at(3, 8), at(3, 10))]
+        );
+        assert_eq!(report.lateness, chrono::Duration::hours(14));
+    }
+
+    #[test]
+    fn test_penalize_move() {
+        let n: usize = 6;
+        let day = |d: u32, h: u32| Utc.with_ymd_and_hms(2021, 1, d, h, 0, 0).unwrap();
+        let time_input = TimeInput {
+            job_durations: (0..n)
+                .map(|i| chrono::Duration::hours((i % 3 + 1) as i64))
+                .collect(),
+            time_windows: (0..n)
+                .map(|i| {
+                    TimeWindows::new(vec![
+                        TimeWindow::new(day(1 + (i % 3) as u32, 8), day(1 + (i % 3) as u32, 14)),
+                        TimeWindow::new(day(5, 8), day(5, 14)),
+                    ])
+                })
+                .collect(),
+            operation_times: Some(OperationTimes::new(
+                NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+                NaiveTime::from_hms_opt(16, 0, 0).unwrap(),
+                None,
+            )),
+            duration_matrix: (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| chrono::Duration::minutes(((i * 7 + j * 3) % 11) as i64 * 20))
+                        .collect()
+                })
+                .collect(),
+            travel_duration_until_break: Some(chrono::Duration::hours(1)),
+            break_duration: Some(chrono::Duration::minutes(15)),
+            ..TimeInput::default()
+        };
+        let distance_matrix = DistanceMatrix::new(
+            (0..n)
+                .map(|i| (0..n).map(|j| ((i * 5 + j) % 7) as u64).collect())
+                .collect(),
+        );
+        let penalizer = Penalizer::new(
+            DistancePenalizer::new(distance_matrix),
+            Some(TimePenalizer::new(time_input)),
+        );
+        let routes = vec![
+            Route::new(vec![0, 1, 2, 3, 4, 5]),
+            Route::with_vehicle(vec![1, 2, 3, 4], Vehicle::new(0, 5)),
+            Route::with_vehicle(vec![1, 2, 3, 4, 5], Vehicle::with_endpoints(Some(0), None)),
+            Route::with_vehicle(vec![0, 1, 2, 3, 4], Vehicle::with_endpoints(None, Some(5))),
+        ];
+        for route in routes {
+            let solution = penalizer.penalize(route.clone(), false);
+            for i in 0..route.len() {
+                for j in i + 1..route.len() {
+                    let delta =
+                        penalizer
+                            .distance_penalizer
+                            .two_opt_delta(&solution.distance_cache, i, j);
+                    let (distance, time_report, _) = penalizer.penalize_move(
+                        &solution,
+                        &mut |route| crate::local_moves::two_opt(route, i, j),
+                        delta,
+                        i,
+                    );
+                    let time_report = time_report.unwrap();
+                    let mut new_route = route.clone();
+                    crate::local_moves::two_opt(&mut new_route, i, j);
+                    let expected = penalizer.penalize(new_route, false);
+                    let expected_time_report = expected.time_report.unwrap();
+                    assert_eq!(distance, expected.distance);
+                    assert_eq!(time_report.end_time, expected_time_report.end_time);
+                    assert_eq!(time_report.lateness, expected_time_report.lateness);
+                    assert_eq!(time_report.waiting_time, expected_time_report.waiting_time);
+                    assert_eq!(time_report.break_time, expected_time_report.break_time);
+                    assert_eq!(time_report.job_splits, expected_time_report.job_splits);
+                }
+            }
+            // inter-route moves change both routes from some position on,
+            // e.g. handing over the tails from there to the other route
+            let other =
+                penalizer.penalize(Route::with_vehicle(vec![2, 4], Vehicle::new(0, 5)), false);
+            for i in 0..=route.len() {
+                for j in 0..=other.route.len() {
+                    let deltas = penalizer.distance_penalizer.cross_delta(
+                        &solution.distance_cache,
+                        &other.distance_cache,
+                        i,
+                        j,
+                    );
+                    let reports = penalizer.penalize_inter_move(
+                        [&solution, &other],
+                        &mut |route1, route2| crate::local_moves::cross(route1, route2, i, j),
+                        deltas,
+                        [i, j],
+                    );
+                    let mut new_route1 = route.clone();
+                    let mut new_route2 = other.route.clone();
+                    crate::local_moves::cross(&mut new_route1, &mut new_route2, i, j);
+                    for ((distance, time_report, _), new_route) in
+                        reports.into_iter().zip([new_route1, new_route2])
+                    {
+                        let time_report = time_report.unwrap();
+                        let expected = penalizer.penalize(new_route, false);
+                        let expected_time_report = expected.time_report.unwrap();
+                        assert_eq!(distance, expected.distance);
+                        assert_eq!(time_report.end_time, expected_time_report.end_time);
+                        assert_eq!(time_report.lateness, expected_time_report.lateness);
+                        assert_eq!(time_report.waiting_time, expected_time_report.waiting_time);
+                    }
+                }
+            }
+        }
+        // while searching, only the totals over all routes are combined
+        let solution = penalizer.penalize_routes(
+            vec![
+                Route::with_vehicle(vec![1, 2], Vehicle::new(0, 5)),
+                Route::with_vehicle(vec![3, 4], Vehicle::new(0, 5)),
+            ],
+            true,
+        );
+        let (distance, time_report, _) = solution.totals_with(&[]);
+        let time_report = time_report.unwrap();
+        let full_report = solution.time_report.unwrap();
+        assert_eq!(distance, solution.distance);
+        assert_eq!(time_report.duration, full_report.duration);
+        assert_eq!(time_report.lateness, full_report.lateness);
+        assert!(time_report.schedule.is_empty() && time_report.stops.is_empty());
+        assert!(!full_report.schedule.is_empty() && !full_report.stops.is_empty());
+    }
+
+    #[test]
+    fn test_penalize_routes() {
+        let distance_matrix = DistanceMatrix::new(vec![
+            vec![0, 1, 2, 3],
+            vec![1, 0, 4, 5],
+            vec![2, 4, 0, 6],
+            vec![3, 5, 6, 0],
+        ]);
+        let penalizer = Penalizer::new(DistancePenalizer::new(distance_matrix), None);
+        let solution = penalizer.penalize_routes(
+            vec![
+                Route::with_vehicle(vec![1, 2], Vehicle::new(0, 0)),
+                Route::with_vehicle(vec![], Vehicle::new(0, 3)),
+                Route::with_vehicle(vec![2], Vehicle::new(3, 0)),
+            ],
+            false,
+        );
+        assert_eq!(solution.routes[0].distance, 7);
+        assert_eq!(solution.routes[1].distance, 0);
+        assert_eq!(solution.routes[2].distance, 8);
+        assert_eq!(solution.distance, 15);
+        assert!(solution.time_report.is_none());
+    }
+
     #[test]
     fn test_is_better() {
         let distance_matrix =
             DistanceMatrix::new(vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]]);
-        let time_input = Some(TimeInput {
+        let time_input = TimeInput {
             job_durations: vec![chrono::Duration::hours(3); 3],
             time_windows: vec![
                 TimeWindows::new(vec![
@@ -314,9 +1201,10 @@ mod tests {
             ],
             travel_duration_until_break: None,
             break_duration: None,
-        });
+            ..TimeInput::default()
+        };
         let distance_penalizer = DistancePenalizer::new(distance_matrix);
-        let time_penalizer = TimePenalizer::new(time_input.unwrap());
+        let time_penalizer = TimePenalizer::new(time_input);
         let penalizer = Penalizer::new(distance_penalizer, Some(time_penalizer));
         let route1 = Route::new(vec![0, 1, 2]);
         let route2 = Route::new(vec![2, 1, 0]);
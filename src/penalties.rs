@@ -1,2 +1,3 @@
+pub mod capacity;
 pub mod distance;
 pub mod time;
@@ -0,0 +1,110 @@
+pub mod capacity_input;
+pub mod capacity_output;
+
+use capacity_input::{CapacityInput, PairRole};
+use capacity_output::CapacityOutput;
+
+use crate::route::Route;
+
+pub struct CapacityPenalizer {
+    capacity_input: CapacityInput,
+}
+
+impl CapacityPenalizer {
+    pub fn new(capacity_input: CapacityInput) -> CapacityPenalizer {
+        CapacityPenalizer { capacity_input }
+    }
+
+    /// Follows the load of the vehicle along the route.
+    /// It leaves its start with everything its jobs deliver, except for the deliveries of pairs,
+    /// which are loaded at their pickups instead.
+    pub fn penalize(&self, route: &Route, build_loads: bool) -> CapacityOutput {
+        let capacity_input = &self.capacity_input;
+        let capacity = capacity_input.capacity.as_deref();
+        let mut load = vec![0; capacity_input.dimensions()];
+        for &location in &route.sequence {
+            if !matches!(capacity_input.role(location), Some(PairRole::Delivery(_))) {
+                for (load, &demand) in load.iter_mut().zip(capacity_input.demand(location)) {
+                    *load += demand.max(0);
+                }
+            }
+        }
+        let mut capacity_output = CapacityOutput::default();
+        capacity_output.add_load(&load, capacity, build_loads);
+        // the pairs whose pickup is on the route, but their delivery not yet
+        let mut picked_up = vec![];
+        for &location in &route.sequence {
+            for (load, &demand) in load.iter_mut().zip(capacity_input.demand(location)) {
+                *load -= demand;
+            }
+            capacity_output.add_load(&load, capacity, build_loads);
+            match capacity_input.role(location) {
+                Some(PairRole::Pickup(pair)) => picked_up.push(pair),
+                Some(PairRole::Delivery(pair)) => match picked_up.iter().position(|&p| p == pair) {
+                    Some(i) => {
+                        picked_up.swap_remove(i);
+                    }
+                    None => capacity_output.add_violated_pair(pair),
+                },
+                None => {}
+            }
+        }
+        // the deliveries of these are on another route, or before their pickup
+        for pair in picked_up {
+            capacity_output.add_violated_pair(pair);
+        }
+        capacity_output.violated_pairs.sort();
+        capacity_output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route::Vehicle;
+
+    #[test]
+    fn test_penalize() {
+        // location 0 is the depot, 1 delivers 3 units, 2 picks up 2 units for 3,
+        // and 4 picks up 1 unit that is brought back to the depot
+        let capacity_input = CapacityInput::new(
+            vec![vec![0], vec![3], vec![-2], vec![2], vec![-1]],
+            Some(vec![4]),
+            vec![(2, 3)],
+        );
+        let penalizer = CapacityPenalizer::new(capacity_input);
+        let report = penalizer.penalize(
+            &Route::with_vehicle(vec![1, 2, 3, 4], Vehicle::new(0, 0)),
+            true,
+        );
+        assert_eq!(
+            report.loads,
+            vec![vec![3], vec![0], vec![2], vec![0], vec![1]]
+        );
+        assert!(report.is_feasible());
+        // picking up before delivering overloads the vehicle
+        let report = penalizer.penalize(
+            &Route::with_vehicle(vec![2, 1, 3, 4], Vehicle::new(0, 0)),
+            false,
+        );
+        assert_eq!(report.overload, 1);
+        assert!(report.violated_pairs.is_empty());
+        assert!(report.loads.is_empty());
+        // delivering before the pickup violates the pair
+        let report = penalizer.penalize(
+            &Route::with_vehicle(vec![1, 3, 2, 4], Vehicle::new(0, 0)),
+            true,
+        );
+        assert_eq!(report.violated_pairs, vec![0]);
+        assert_eq!(report.loads[2], vec![-2]);
+        // and so does splitting the pair over two routes, but only once
+        let first = penalizer.penalize(&Route::with_vehicle(vec![1, 2], Vehicle::new(0, 0)), false);
+        let second =
+            penalizer.penalize(&Route::with_vehicle(vec![3, 4], Vehicle::new(0, 0)), false);
+        assert_eq!(first.violated_pairs, vec![0]);
+        assert_eq!(second.violated_pairs, vec![0]);
+        let combined = CapacityOutput::combine(&[&first, &second]).unwrap();
+        assert_eq!(combined.violated_pairs, vec![0]);
+        assert!(!combined.is_feasible());
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::BTreeSet;
+
+use crate::{input::error::InputError, route::Vehicle};
+
+/// The part a location plays in a pickup and delivery pair, with the index of the pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairRole {
+    Pickup(usize),
+    Delivery(usize),
+}
+
+/// The capacity of the vehicles and the loads of the jobs, in any number of dimensions,
+/// e.g. volume and weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapacityInput {
+    /// What the job at each location delivers (positive) or picks up (negative) in each dimension.
+    /// Empty if only pairs are given.
+    pub demands: Vec<Vec<i64>>,
+    /// The capacity of each vehicle in each dimension, unlimited if None.
+    pub capacity: Option<Vec<u64>>,
+    /// (pickup, delivery) pairs that have to be served by the same route, the pickup first.
+    /// The delivery brings what the pickup picked up instead of a load from the depot.
+    pub pickups_deliveries: Vec<(usize, usize)>,
+    roles: Vec<Option<PairRole>>,
+}
+
+impl CapacityInput {
+    pub fn new(
+        demands: Vec<Vec<i64>>,
+        capacity: Option<Vec<u64>>,
+        pickups_deliveries: Vec<(usize, usize)>,
+    ) -> CapacityInput {
+        let n = pickups_deliveries
+            .iter()
+            .map(|&(pickup, delivery)| pickup.max(delivery) + 1)
+            .max()
+            .unwrap_or(0);
+        let mut roles = vec![None; n];
+        for (pair, &(pickup, delivery)) in pickups_deliveries.iter().enumerate() {
+            roles[pickup] = Some(PairRole::Pickup(pair));
+            roles[delivery] = Some(PairRole::Delivery(pair));
+        }
+        CapacityInput {
+            demands,
+            capacity,
+            pickups_deliveries,
+            roles,
+        }
+    }
+
+    /// The number of dimensions of the loads.
+    pub fn dimensions(&self) -> usize {
+        match &self.capacity {
+            Some(capacity) => capacity.len(),
+            None => self.demands.first().map_or(0, Vec::len),
+        }
+    }
+
+    /// The demand of the job at the location, empty without demands.
+    pub fn demand(&self, location: usize) -> &[i64] {
+        self.demands.get(location).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn role(&self, location: usize) -> Option<PairRole> {
+        self.roles.get(location).copied().flatten()
+    }
+}
+
+/// Validates the demands, the capacity and the pickup and delivery pairs.
+/// Each location may be part of at most one pair, and depots of the vehicles of none.
+/// None if none of them is given.
+pub fn transform(
+    demands: Option<Vec<Vec<i64>>>,
+    capacity: Option<Vec<u64>>,
+    pickups_deliveries: Option<Vec<(usize, usize)>>,
+    vehicles: &Option<Vec<Vehicle>>,
+    n: usize,
+) -> Result<Option<CapacityInput>, InputError> {
+    if demands.is_none() && capacity.is_none() && pickups_deliveries.is_none() {
+        return Ok(None);
+    }
+    let demands = demands.unwrap_or_default();
+    let dimensions = capacity
+        .as_ref()
+        .map(Vec::len)
+        .or(demands.first().map(Vec::len))
+        .unwrap_or(0);
+    if let Some(location) = demands.iter().position(|demand| demand.len() != dimensions) {
+        return Err(InputError::DimensionMismatch {
+            field: "demands",
+            location,
+            len: demands[location].len(),
+            expected: dimensions,
+        });
+    }
+    let pickups_deliveries = pickups_deliveries.unwrap_or_default();
+    let mut paired = BTreeSet::new();
+    for (pair, &(pickup, delivery)) in pickups_deliveries.iter().enumerate() {
+        for location in [pickup, delivery] {
+            if location >= n {
+                return Err(InputError::LocationOutOfRange {
+                    field: "pickups_deliveries",
+                    position: pair,
+                    location,
+                });
+            }
+            if vehicles
+                .iter()
+                .flatten()
+                .any(|vehicle| vehicle.is_depot(location))
+            {
+                return Err(InputError::DepotLocation {
+                    field: "pickups_deliveries",
+                    position: pair,
+                    location,
+                });
+            }
+            if !paired.insert(location) {
+                return Err(InputError::DuplicateLocation {
+                    field: "pickups_deliveries",
+                    location,
+                });
+            }
+        }
+        // without demands, there is nothing to balance;
+        // a pickup only picks up, so the delivery cannot bring back what it delivered
+        let unbalanced = match (demands.get(pickup), demands.get(delivery)) {
+            (Some(picked_up), Some(delivered)) => picked_up
+                .iter()
+                .zip(delivered)
+                .any(|(&picked_up, &delivered)| picked_up > 0 || -picked_up != delivered),
+            _ => false,
+        };
+        if unbalanced {
+            return Err(InputError::UnbalancedPickupDelivery { pair });
+        }
+    }
+    Ok(Some(CapacityInput::new(
+        demands,
+        capacity,
+        pickups_deliveries,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform() {
+        let capacity_input = transform(
+            Some(vec![vec![0, 0], vec![-2, -1], vec![2, 1], vec![3, 0]]),
+            Some(vec![4, 2]),
+            Some(vec![(1, 2)]),
+            &None,
+            4,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(capacity_input.dimensions(), 2);
+        assert_eq!(capacity_input.role(1), Some(PairRole::Pickup(0)));
+        assert_eq!(capacity_input.role(2), Some(PairRole::Delivery(0)));
+        assert_eq!(capacity_input.role(3), None);
+        assert_eq!(capacity_input.demand(3), &[3, 0]);
+        // pairs without any demands
+        let capacity_input = transform(None, None, Some(vec![(0, 1)]), &None, 2)
+            .unwrap()
+            .unwrap();
+        assert_eq!(capacity_input.demand(0), &[] as &[i64]);
+        assert_eq!(transform(None, None, None, &None, 2), Ok(None));
+    }
+
+    #[test]
+    fn test_transform_errors() {
+        assert_eq!(
+            transform(Some(vec![vec![1], vec![1, 2]]), None, None, &None, 2),
+            Err(InputError::DimensionMismatch {
+                field: "demands",
+                location: 1,
+                len: 2,
+                expected: 1
+            })
+        );
+        assert_eq!(
+            transform(
+                Some(vec![vec![1], vec![2]]),
+                Some(vec![3, 3]),
+                None,
+                &None,
+                2
+            ),
+            Err(InputError::DimensionMismatch {
+                field: "demands",
+                location: 0,
+                len: 1,
+                expected: 2
+            })
+        );
+        assert_eq!(
+            transform(None, None, Some(vec![(0, 1), (2, 3)]), &None, 3),
+            Err(InputError::LocationOutOfRange {
+                field: "pickups_deliveries",
+                position: 1,
+                location: 3
+            })
+        );
+        assert_eq!(
+            transform(None, None, Some(vec![(0, 1), (1, 2)]), &None, 3),
+            Err(InputError::DuplicateLocation {
+                field: "pickups_deliveries",
+                location: 1
+            })
+        );
+        assert_eq!(
+            transform(
+                Some(vec![vec![-2], vec![1]]),
+                None,
+                Some(vec![(0, 1)]),
+                &None,
+                2
+            ),
+            Err(InputError::UnbalancedPickupDelivery { pair: 0 })
+        );
+        // the pickup delivers in the first dimension, which the delivery would pick up again
+        assert_eq!(
+            transform(
+                Some(vec![vec![2, -1], vec![-2, 1]]),
+                None,
+                Some(vec![(0, 1)]),
+                &None,
+                2
+            ),
+            Err(InputError::UnbalancedPickupDelivery { pair: 0 })
+        );
+        // depots are not jobs that could be paired
+        let vehicles = Some(vec![
+            Vehicle::new(0, 0),
+            Vehicle::with_endpoints(Some(1), None),
+        ]);
+        assert_eq!(
+            transform(None, None, Some(vec![(2, 3), (1, 4)]), &vehicles, 5),
+            Err(InputError::DepotLocation {
+                field: "pickups_deliveries",
+                position: 1,
+                location: 1
+            })
+        );
+        assert!(transform(None, None, Some(vec![(2, 3)]), &vehicles, 5).is_ok());
+    }
+}
@@ -0,0 +1,66 @@
+/// Capacity report of a route, or of several routes combined.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapacityOutput {
+    /// The total amount by which the load exceeds the capacity,
+    /// summed over all dimensions, when leaving the start and after each job.
+    pub overload: u64,
+    /// The indices of the pickup and delivery pairs that are not served by the same route, pickup first.
+    pub violated_pairs: Vec<usize>,
+    /// The load in each dimension when leaving the start and after each job, built on request.
+    pub loads: Vec<Vec<i64>>,
+}
+
+impl CapacityOutput {
+    /// Adds the load the vehicle carries at one point of the route.
+    pub fn add_load(&mut self, load: &[i64], capacity: Option<&[u64]>, build_loads: bool) {
+        if let Some(capacity) = capacity {
+            self.overload += load
+                .iter()
+                .zip(capacity)
+                .map(|(&load, &capacity)| (load - capacity as i64).max(0) as u64)
+                .sum::<u64>();
+        }
+        if build_loads {
+            self.loads.push(load.to_vec());
+        }
+    }
+
+    pub fn add_violated_pair(&mut self, pair: usize) {
+        if !self.violated_pairs.contains(&pair) {
+            self.violated_pairs.push(pair);
+        }
+    }
+
+    /// Whether no load exceeds the capacity and every pair is served in order.
+    pub fn is_feasible(&self) -> bool {
+        self.overload == 0 && self.violated_pairs.is_empty()
+    }
+
+    /// Sums up the reports of several routes, appending their loads one after the other.
+    /// A pair split over two routes is violated by both, but only counts once.
+    pub fn combine(reports: &[&CapacityOutput]) -> Option<CapacityOutput> {
+        let mut combined = CapacityOutput::combine_totals(reports)?;
+        for report in reports {
+            combined.loads.extend(report.loads.iter().cloned());
+        }
+        Some(combined)
+    }
+
+    /// Like `combine`, but without the loads, so moves can be compared cheaply while searching.
+    pub fn combine_totals(reports: &[&CapacityOutput]) -> Option<CapacityOutput> {
+        let (first, rest) = reports.split_first()?;
+        let mut combined = CapacityOutput {
+            overload: first.overload,
+            violated_pairs: first.violated_pairs.clone(),
+            loads: vec![],
+        };
+        for report in rest {
+            combined.overload += report.overload;
+            for &pair in &report.violated_pairs {
+                combined.add_violated_pair(pair);
+            }
+        }
+        combined.violated_pairs.sort();
+        Some(combined)
+    }
+}
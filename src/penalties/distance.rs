@@ -2,7 +2,37 @@ pub mod distance_matrix;
 
 pub use distance_matrix::DistanceMatrix;
 
-use crate::route::Route;
+use crate::{local_moves::ThreeOpt, route::Route};
+
+/// Prefix sums of the leg distances of a route, driving forward and backward.
+/// With them, the distance of a route whose sequence is rearranged from
+/// a few (possibly reversed) blocks of the old sequence is computed in O(1).
+#[derive(Debug, Clone)]
+pub struct DistanceCache {
+    /// The sequence of the route, framed by the depots of its vehicle.
+    path: Vec<usize>,
+    /// Number of depots in front of the sequence in the path.
+    offset: usize,
+    /// Number of depots behind the sequence in the path.
+    tail: usize,
+    /// Whether the route returns from its last to its first location.
+    closed: bool,
+    forward: Vec<u64>,
+    backward: Vec<u64>,
+}
+
+impl DistanceCache {
+    /// Length of the cached sequence, without the depots.
+    fn len(&self) -> usize {
+        self.path.len() - self.offset - self.tail
+    }
+}
+
+/// A block [start, end) of the old sequence, possibly driven in reverse.
+type Block = (usize, usize, bool);
+
+/// A block of the old sequence of one of two routes, for moves between them.
+type Segment<'a> = (&'a DistanceCache, Block);
 
 pub struct DistancePenalizer {
     distance_matrix: DistanceMatrix,
@@ -13,14 +43,485 @@ impl DistancePenalizer {
         DistancePenalizer { distance_matrix }
     }
 
+    pub fn distance_matrix(&self) -> &DistanceMatrix {
+        &self.distance_matrix
+    }
+
     pub fn penalize(&self, route: &Route) -> u64 {
+        route
+            .legs()
+            .into_iter()
+            .map(|(from, to)| self.distance_matrix.distance(from, to))
+            .sum()
+    }
+
+    pub fn cache(&self, route: &Route) -> DistanceCache {
+        let path: Vec<usize> = route
+            .start_depot()
+            .into_iter()
+            .chain(route.sequence.iter().copied())
+            .chain(route.end_depot())
+            .collect();
+        let mut forward = vec![0; path.len()];
+        let mut backward = vec![0; path.len()];
+        for t in 1..path.len() {
+            forward[t] = forward[t - 1] + self.distance_matrix.distance(path[t - 1], path[t]);
+            backward[t] = backward[t - 1] + self.distance_matrix.distance(path[t], path[t - 1]);
+        }
+        DistanceCache {
+            path,
+            offset: route.start_depot().map_or(0, |_| 1),
+            tail: route.end_depot().map_or(0, |_| 1),
+            closed: route.vehicle.is_none(),
+            forward,
+            backward,
+        }
+    }
+
+    /// Distance of the route built from the given blocks of the cached sequence.
+    fn blocks_distance(&self, cache: &DistanceCache, blocks: &[Block]) -> u64 {
+        self.segments_distance(cache, blocks.iter().map(|&block| (cache, block)))
+    }
+
+    /// Distance of the route of `cache` built from the given segments,
+    /// which may also be blocks of the sequence of another route.
+    fn segments_distance<'a>(
+        &self,
+        cache: &'a DistanceCache,
+        segments: impl Iterator<Item = Segment<'a>> + Clone,
+    ) -> u64 {
+        // an empty route of a vehicle does not travel between its depots
+        if segments.clone().all(|(_, (start, end, _))| start >= end) {
+            return 0;
+        }
+        let n = cache.len();
+        // the depots stay where they are
+        let head = (cache, (0, cache.offset, false));
+        let tail = (cache, (n + cache.offset, cache.path.len(), false));
+        let shifted = segments.map(|(source, (start, end, reversed))| {
+            (
+                source,
+                (start + source.offset, end + source.offset, reversed),
+            )
+        });
         let mut distance = 0;
-        for i in 0..route.len() - 1 {
-            distance += self.distance_matrix.distance(route[i], route[i + 1]);
+        let mut first = None;
+        let mut last = None;
+        for (source, (start, end, reversed)) in std::iter::once(head).chain(shifted).chain([tail]) {
+            if start >= end {
+                continue;
+            }
+            let (from, to, inner) = match reversed {
+                false => (
+                    source.path[start],
+                    source.path[end - 1],
+                    source.forward[end - 1] - source.forward[start],
+                ),
+                true => (
+                    source.path[end - 1],
+                    source.path[start],
+                    source.backward[end - 1] - source.backward[start],
+                ),
+            };
+            if let Some(last) = last {
+                distance += self.distance_matrix.distance(last, from);
+            }
+            distance += inner;
+            first.get_or_insert(from);
+            last = Some(to);
+        }
+        if let (true, Some(first), Some(last)) = (cache.closed, first, last) {
+            distance += self.distance_matrix.distance(last, first);
         }
         distance
-            + self
-                .distance_matrix
-                .distance(route[route.len() - 1], route[0])
+    }
+
+    fn delta(&self, cache: &DistanceCache, blocks: &[Block]) -> i64 {
+        let n = cache.len();
+        self.blocks_distance(cache, blocks) as i64
+            - self.blocks_distance(cache, &[(0, n, false)]) as i64
+    }
+
+    /// Like `delta`, for a route built from segments of its own and another route.
+    fn segments_delta(&self, cache: &DistanceCache, segments: &[Segment]) -> i64 {
+        let n = cache.len();
+        self.segments_distance(cache, segments.iter().copied()) as i64
+            - self.blocks_distance(cache, &[(0, n, false)]) as i64
+    }
+
+    /// Changes of distance of both routes when moving the location at i of the first route
+    /// in front of position j of the second, where 0 <= j <= n2.
+    pub fn relocate_delta(
+        &self,
+        cache1: &DistanceCache,
+        cache2: &DistanceCache,
+        i: usize,
+        j: usize,
+    ) -> [i64; 2] {
+        let (n1, n2) = (cache1.len(), cache2.len());
+        [
+            self.delta(cache1, &[(0, i, false), (i + 1, n1, false)]),
+            self.segments_delta(
+                cache2,
+                &[
+                    (cache2, (0, j, false)),
+                    (cache1, (i, i + 1, false)),
+                    (cache2, (j, n2, false)),
+                ],
+            ),
+        ]
+    }
+
+    /// Changes of distance of both routes when exchanging the location at i of the first route
+    /// with the one at j of the second.
+    pub fn exchange_delta(
+        &self,
+        cache1: &DistanceCache,
+        cache2: &DistanceCache,
+        i: usize,
+        j: usize,
+    ) -> [i64; 2] {
+        let (n1, n2) = (cache1.len(), cache2.len());
+        [
+            self.segments_delta(
+                cache1,
+                &[
+                    (cache1, (0, i, false)),
+                    (cache2, (j, j + 1, false)),
+                    (cache1, (i + 1, n1, false)),
+                ],
+            ),
+            self.segments_delta(
+                cache2,
+                &[
+                    (cache2, (0, j, false)),
+                    (cache1, (i, i + 1, false)),
+                    (cache2, (j + 1, n2, false)),
+                ],
+            ),
+        ]
+    }
+
+    /// Changes of distance of both routes when exchanging their tails from i and j on,
+    /// where 0 <= i <= n1 and 0 <= j <= n2.
+    pub fn cross_delta(
+        &self,
+        cache1: &DistanceCache,
+        cache2: &DistanceCache,
+        i: usize,
+        j: usize,
+    ) -> [i64; 2] {
+        let (n1, n2) = (cache1.len(), cache2.len());
+        [
+            self.segments_delta(cache1, &[(cache1, (0, i, false)), (cache2, (j, n2, false))]),
+            self.segments_delta(cache2, &[(cache2, (0, j, false)), (cache1, (i, n1, false))]),
+        ]
+    }
+
+    /// Change of distance when reversing the sequence between i and j.
+    pub fn two_opt_delta(&self, cache: &DistanceCache, i: usize, j: usize) -> i64 {
+        let n = cache.len();
+        self.delta(cache, &[(0, i, false), (i, j + 1, true), (j + 1, n, false)])
+    }
+
+    /// Change of distance when swapping the locations at i and j.
+    pub fn swap_delta(&self, cache: &DistanceCache, i: usize, j: usize) -> i64 {
+        let n = cache.len();
+        self.delta(
+            cache,
+            &[
+                (0, i, false),
+                (j, j + 1, false),
+                (i + 1, j, false),
+                (i, i + 1, false),
+                (j + 1, n, false),
+            ],
+        )
+    }
+
+    /// Change of distance when rotating the sequence between i and j by k to the left.
+    pub fn shift_left_delta(&self, cache: &DistanceCache, i: usize, j: usize, k: usize) -> i64 {
+        let n = cache.len();
+        self.delta(
+            cache,
+            &[
+                (0, i, false),
+                (i + k, j + 1, false),
+                (i, i + k, false),
+                (j + 1, n, false),
+            ],
+        )
+    }
+
+    /// Change of distance when rotating the sequence between i and j by k to the right.
+    pub fn shift_right_delta(&self, cache: &DistanceCache, i: usize, j: usize, k: usize) -> i64 {
+        let n = cache.len();
+        self.delta(
+            cache,
+            &[
+                (0, i, false),
+                (j + 1 - k, j + 1, false),
+                (i, j + 1 - k, false),
+                (j + 1, n, false),
+            ],
+        )
+    }
+
+    /// Change of distance when moving the first k locations between i and j reversed behind j.
+    pub fn reversed_shift_left_delta(
+        &self,
+        cache: &DistanceCache,
+        i: usize,
+        j: usize,
+        k: usize,
+    ) -> i64 {
+        let n = cache.len();
+        self.delta(
+            cache,
+            &[
+                (0, i, false),
+                (i + k, j + 1, false),
+                (i, i + k, true),
+                (j + 1, n, false),
+            ],
+        )
+    }
+
+    /// Change of distance when moving the last k locations between i and j reversed in front of i.
+    pub fn reversed_shift_right_delta(
+        &self,
+        cache: &DistanceCache,
+        i: usize,
+        j: usize,
+        k: usize,
+    ) -> i64 {
+        let n = cache.len();
+        self.delta(
+            cache,
+            &[
+                (0, i, false),
+                (j + 1 - k, j + 1, true),
+                (i, j + 1 - k, false),
+                (j + 1, n, false),
+            ],
+        )
+    }
+
+    /// Change of distance when reconnecting the segments [i, j) and [j, k] by 3-opt.
+    pub fn three_opt_delta(
+        &self,
+        cache: &DistanceCache,
+        i: usize,
+        j: usize,
+        k: usize,
+        reconnection: ThreeOpt,
+    ) -> i64 {
+        let n = cache.len();
+        let (first, second) = match reconnection {
+            ThreeOpt::ReverseBoth => ((i, j, true), (j, k + 1, true)),
+            ThreeOpt::ReverseFirst => ((j, k + 1, false), (i, j, true)),
+            ThreeOpt::ReverseSecond => ((j, k + 1, true), (i, j, false)),
+        };
+        self.delta(cache, &[(0, i, false), first, second, (k + 1, n, false)])
+    }
+
+    /// Change of distance when removing the location at i.
+    pub fn remove_delta(&self, cache: &DistanceCache, i: usize) -> i64 {
+        let n = cache.len();
+        self.delta(cache, &[(0, i, false), (i + 1, n, false)])
+    }
+
+    /// Change of distance when inserting `location` in front of position j, where 0 <= j <= n.
+    pub fn insert_delta(&self, cache: &DistanceCache, j: usize, location: usize) -> i64 {
+        let n = cache.len();
+        let distance = |from, to| self.distance_matrix.distance(from, to) as i64;
+        let position = j + cache.offset;
+        let (previous, next) = match cache.closed {
+            true if n == 0 => return distance(location, location),
+            // a closed tour returns from its last to its first location
+            true => (cache.path[(position + n - 1) % n], cache.path[position % n]),
+            false => match (position.checked_sub(1), cache.path.get(position)) {
+                (Some(previous), Some(&next)) => (cache.path[previous], next),
+                (Some(previous), None) => return distance(cache.path[previous], location),
+                (None, Some(&next)) => return distance(location, next),
+                (None, None) => return 0,
+            },
+        };
+        let mut delta = distance(previous, location) + distance(location, next);
+        // an empty route of a vehicle does not travel between its depots
+        if n > 0 {
+            delta -= distance(previous, next);
+        }
+        delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{local_moves, route::Vehicle};
+
+    fn distance_penalizer() -> DistancePenalizer {
+        // an asymmetric matrix, so reversing a segment changes its distance
+        DistancePenalizer::new(DistanceMatrix::new(
+            (0..8)
+                .map(|i| (0..8).map(|j| ((i * 7 + j * 3) % 11) as u64).collect())
+                .collect(),
+        ))
+    }
+
+    #[test]
+    fn test_deltas() {
+        let penalizer = distance_penalizer();
+        let routes = vec![
+            Route::new(vec![0, 1, 2, 3, 4, 5, 6, 7]),
+            Route::with_vehicle(vec![1, 2, 3, 4, 5, 6], Vehicle::new(0, 7)),
+            Route::with_vehicle(
+                vec![1, 2, 3, 4, 5, 6, 7],
+                Vehicle::with_endpoints(Some(0), None),
+            ),
+            Route::with_vehicle(
+                vec![0, 1, 2, 3, 4, 5, 6],
+                Vehicle::with_endpoints(None, Some(7)),
+            ),
+            Route::with_vehicle(
+                vec![0, 1, 2, 3, 4, 5, 6, 7],
+                Vehicle::with_endpoints(None, None),
+            ),
+            Route::with_vehicle(vec![], Vehicle::new(0, 7)),
+            Route::with_vehicle(vec![3], Vehicle::with_endpoints(Some(0), None)),
+            Route::with_vehicle(vec![3], Vehicle::new(0, 7)),
+            Route::new(vec![]),
+            Route::new(vec![3]),
+        ];
+        for route in routes {
+            let distance = penalizer.penalize(&route) as i64;
+            let cache = penalizer.cache(&route);
+            for i in 0..route.len() {
+                let mut new_route = route.clone();
+                local_moves::remove(&mut new_route, i);
+                assert_eq!(
+                    penalizer.remove_delta(&cache, i),
+                    penalizer.penalize(&new_route) as i64 - distance
+                );
+            }
+            for j in 0..=route.len() {
+                let mut new_route = route.clone();
+                local_moves::insert(&mut new_route, j, 5);
+                assert_eq!(
+                    penalizer.insert_delta(&cache, j, 5),
+                    penalizer.penalize(&new_route) as i64 - distance
+                );
+            }
+            for i in 0..route.len() {
+                for j in i + 1..route.len() {
+                    let mut new_route = route.clone();
+                    local_moves::two_opt(&mut new_route, i, j);
+                    assert_eq!(
+                        penalizer.two_opt_delta(&cache, i, j),
+                        penalizer.penalize(&new_route) as i64 - distance
+                    );
+                    let mut new_route = route.clone();
+                    local_moves::swap(&mut new_route, i, j);
+                    assert_eq!(
+                        penalizer.swap_delta(&cache, i, j),
+                        penalizer.penalize(&new_route) as i64 - distance
+                    );
+                    let mut new_route = route.clone();
+                    local_moves::one_shift_left(&mut new_route, i, j);
+                    assert_eq!(
+                        penalizer.shift_left_delta(&cache, i, j, 1),
+                        penalizer.penalize(&new_route) as i64 - distance
+                    );
+                    if j - i >= 2 {
+                        let mut new_route = route.clone();
+                        local_moves::two_shift_right(&mut new_route, i, j);
+                        assert_eq!(
+                            penalizer.shift_right_delta(&cache, i, j, 2),
+                            penalizer.penalize(&new_route) as i64 - distance
+                        );
+                        let mut new_route = route.clone();
+                        local_moves::reversed_shift_left(&mut new_route, i, j, 2);
+                        assert_eq!(
+                            penalizer.reversed_shift_left_delta(&cache, i, j, 2),
+                            penalizer.penalize(&new_route) as i64 - distance
+                        );
+                        let mut new_route = route.clone();
+                        local_moves::reversed_shift_right(&mut new_route, i, j, 2);
+                        assert_eq!(
+                            penalizer.reversed_shift_right_delta(&cache, i, j, 2),
+                            penalizer.penalize(&new_route) as i64 - distance
+                        );
+                    }
+                    for k in j..route.len() {
+                        for reconnection in [
+                            ThreeOpt::ReverseBoth,
+                            ThreeOpt::ReverseFirst,
+                            ThreeOpt::ReverseSecond,
+                        ] {
+                            let mut new_route = route.clone();
+                            local_moves::three_opt(&mut new_route, i, j, k, reconnection);
+                            assert_eq!(
+                                penalizer.three_opt_delta(&cache, i, j, k, reconnection),
+                                penalizer.penalize(&new_route) as i64 - distance
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_inter_route_deltas() {
+        let penalizer = distance_penalizer();
+        let routes = vec![
+            Route::with_vehicle(vec![1, 2, 3], Vehicle::new(0, 7)),
+            Route::with_vehicle(vec![4, 5, 6, 7], Vehicle::with_endpoints(Some(0), None)),
+            Route::with_vehicle(vec![2, 6], Vehicle::with_endpoints(None, Some(7))),
+            Route::with_vehicle(vec![], Vehicle::new(0, 7)),
+            Route::with_vehicle(vec![5], Vehicle::with_endpoints(None, None)),
+        ];
+        // the change of distance of both routes after the move
+        let deltas = |route1: &Route, route2: &Route, new_route1: &Route, new_route2: &Route| {
+            [
+                penalizer.penalize(new_route1) as i64 - penalizer.penalize(route1) as i64,
+                penalizer.penalize(new_route2) as i64 - penalizer.penalize(route2) as i64,
+            ]
+        };
+        for route1 in &routes {
+            for route2 in &routes {
+                let cache1 = penalizer.cache(route1);
+                let cache2 = penalizer.cache(route2);
+                for i in 0..=route1.len() {
+                    for j in 0..=route2.len() {
+                        let (mut new_route1, mut new_route2) = (route1.clone(), route2.clone());
+                        local_moves::cross(&mut new_route1, &mut new_route2, i, j);
+                        assert_eq!(
+                            penalizer.cross_delta(&cache1, &cache2, i, j),
+                            deltas(route1, route2, &new_route1, &new_route2)
+                        );
+                        if i == route1.len() {
+                            continue;
+                        }
+                        let (mut new_route1, mut new_route2) = (route1.clone(), route2.clone());
+                        local_moves::relocate(&mut new_route1, &mut new_route2, i, j);
+                        assert_eq!(
+                            penalizer.relocate_delta(&cache1, &cache2, i, j),
+                            deltas(route1, route2, &new_route1, &new_route2)
+                        );
+                        if j == route2.len() {
+                            continue;
+                        }
+                        let (mut new_route1, mut new_route2) = (route1.clone(), route2.clone());
+                        local_moves::exchange(&mut new_route1, &mut new_route2, i, j);
+                        assert_eq!(
+                            penalizer.exchange_delta(&cache1, &cache2, i, j),
+                            deltas(route1, route2, &new_route1, &new_route2)
+                        );
+                    }
+                }
+            }
+        }
     }
 }
@@ -11,9 +11,17 @@ impl DistanceMatrix {
         self.matrix[i][j]
     }
 
+    pub fn matrix(&self) -> &[Vec<u64>] {
+        &self.matrix
+    }
+
     pub fn len(&self) -> usize {
         self.matrix.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.matrix.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -1,45 +1,103 @@
 pub mod operation_times;
+pub mod precedences;
+pub mod soft_windows;
 pub mod time_input;
 pub mod time_output;
 pub mod time_windows;
-use std::cmp::max;
+use std::cmp::{max, min};
 
 use chrono::{Duration, Utc};
-use time_input::TimeInput;
-use time_output::{Complete, Incomplete, TimeOutput};
+use precedences::{PrecedenceIndex, PrecedenceViolation};
+use time_input::{SplitPolicy, TimeInput};
+use time_output::{Complete, Incomplete, Service, Stop, TimeOutput};
 use time_windows::TimeWindow;
 
 use crate::route::Route;
 
+/// The state of a schedule right before travelling to a position of the route.
+/// Routes sharing the sequence up to that position can continue from it.
+#[derive(Debug, Clone)]
+pub struct ScheduleState {
+    time_output: TimeOutput<Incomplete>,
+    travel_since_break: Duration,
+}
+
 struct WorkingTimePenalizer<'a> {
     time_input: &'a TimeInput,
+    precedence_index: &'a PrecedenceIndex,
     route: &'a Route,
     time_output: TimeOutput<Incomplete>,
     build_schedule: bool,
+    travel_since_break: Duration,
 }
 
 impl<'a> WorkingTimePenalizer<'a> {
     fn new(
         time_input: &'a TimeInput,
+        precedence_index: &'a PrecedenceIndex,
         route: &'a Route,
         start_time: chrono::DateTime<Utc>,
         build_schedule: bool,
     ) -> WorkingTimePenalizer<'a> {
         WorkingTimePenalizer {
             time_input,
+            precedence_index,
             route,
             time_output: TimeOutput::new(start_time),
             build_schedule,
+            travel_since_break: Duration::zero(),
+        }
+    }
+
+    fn from_state(
+        time_input: &'a TimeInput,
+        precedence_index: &'a PrecedenceIndex,
+        route: &'a Route,
+        state: &ScheduleState,
+    ) -> WorkingTimePenalizer<'a> {
+        WorkingTimePenalizer {
+            time_input,
+            precedence_index,
+            route,
+            time_output: state.time_output.clone(),
+            build_schedule: false,
+            travel_since_break: state.travel_since_break,
+        }
+    }
+
+    fn state(&self) -> ScheduleState {
+        ScheduleState {
+            time_output: self.time_output.clone(),
+            travel_since_break: self.travel_since_break,
         }
     }
 
-    fn finish_schedule(mut self) -> TimeOutput<Complete> {
-        for (i, _) in self.route.sequence.iter().enumerate() {
-            self.execute_job(i);
-            self.execute_travel(i);
+    /// Executes the route from the given position on.
+    /// Unless the schedule is built, the states before each position are returned as well.
+    fn finish_schedule(mut self, from: usize) -> (TimeOutput<Complete>, Vec<ScheduleState>) {
+        let route = self.route;
+        let mut states = vec![];
+        for (i, &location) in route.sequence.iter().enumerate().skip(from) {
+            if !self.build_schedule {
+                states.push(self.state());
+            }
+            match (i, route.start_depot()) {
+                // a vehicle first has to get from its depot to the first job
+                (0, Some(start)) => self.execute_travel(start, location),
+                (0, None) => {}
+                _ => self.execute_travel(route[i - 1], location),
+            }
+            self.execute_job(location);
         }
+        // an open route ends with its last job
+        if let Some(&last) = route.sequence.last() {
+            if let Some(next) = route.next_location(route.len() - 1) {
+                self.execute_travel(last, next);
+            }
+        }
+        self.add_excess();
 
-        self.time_output.complete()
+        (self.time_output.complete(), states)
     }
     fn add_job(&mut self, location: usize, time_window: TimeWindow) {
         // Add waiting between time_output.current_time and time_window.start
@@ -51,9 +109,27 @@ impl<'a> WorkingTimePenalizer<'a> {
         self.time_output
             .add_working(location, time_window, self.build_schedule);
     }
+    /// Penalizes exceeding the planning horizon or the maximum duration of the route
+    /// as overtime, or as excess if the limits are soft.
+    /// The route is scheduled in full either way, the limits only penalize its end.
+    fn add_excess(&mut self) {
+        let limits = &self.time_input.limits;
+        let excess = limits.excess(self.time_output.start_time, self.time_output.duration);
+        match limits.soft {
+            true => self.time_output.add_excess(excess),
+            false => self.time_output.add_overtime(excess),
+        }
+    }
     fn add_split(&mut self) {
         self.time_output.add_split();
     }
+    /// Penalizes a job that misses the preferred time window of its location.
+    fn add_soft_window_penalty(&mut self, location: usize, start_time: chrono::DateTime<Utc>) {
+        if let Some(soft_window) = self.time_input.soft_window(location) {
+            let penalty = soft_window.penalty(location, start_time, self.time_output.end_time);
+            self.time_output.add_penalty(penalty, self.build_schedule);
+        }
+    }
     fn add_lateness(&mut self, location: usize) {
         let time_windows = &self.time_input.time_windows[location];
         let lateness = time_windows.lateness(self.time_output.end_time);
@@ -64,10 +140,37 @@ impl<'a> WorkingTimePenalizer<'a> {
             .start
             .signed_duration_since(self.time_output.end_time);
         self.add_waiting(waiting_duration);
+        self.travel_since_break += time_window.duration();
         self.time_output
             .add_traveling(time_window, self.build_schedule);
     }
+    fn add_break(&mut self, break_duration: Duration) {
+        // If we are outside of the operation times, waiting for them to start again
+        // is long enough to count as the break.
+        let waiting_duration = self
+            .time_input
+            .operation_times
+            .as_ref()
+            .map_or(Duration::zero(), |operation_times| {
+                operation_times.waiting_time(self.time_output.end_time)
+            });
+        if waiting_duration < break_duration {
+            self.time_output.add_break(
+                TimeWindow::new(
+                    self.time_output.end_time,
+                    self.time_output.end_time + break_duration,
+                ),
+                self.build_schedule,
+            );
+        }
+        self.travel_since_break = Duration::zero();
+    }
     fn add_waiting(&mut self, duration: Duration) {
+        if let Some((_, break_duration)) = self.time_input.break_rule() {
+            if duration >= break_duration {
+                self.travel_since_break = Duration::zero();
+            }
+        }
         if duration > chrono::Duration::zero() {
             self.time_output.add_waiting(
                 TimeWindow::new(
@@ -79,13 +182,47 @@ impl<'a> WorkingTimePenalizer<'a> {
         }
     }
 
-    fn execute_job(&mut self, i: usize) {
+    /// Like `OperationTimes::find_next_fitting_time`,
+    /// but without operation times, everything can be done right away and in one go.
+    fn find_next_operation_time(
+        &self,
+        current_time: chrono::DateTime<Utc>,
+        duration: Duration,
+        must_fit: bool,
+    ) -> Option<TimeWindow> {
+        match &self.time_input.operation_times {
+            Some(operation_times) => {
+                operation_times.find_next_fitting_time(current_time, duration, must_fit)
+            }
+            None => Some(TimeWindow::new(current_time, current_time + duration)),
+        }
+    }
+
+    /// The next part of a job that is split according to its policy, in the operation times.
+    /// It starts where at least the minimum part fits, and None if that never happens.
+    fn find_next_part(
+        &self,
+        current_time: chrono::DateTime<Utc>,
+        job_duration: Duration,
+        policy: SplitPolicy,
+    ) -> Option<TimeWindow> {
+        let min_part = min(policy.min_part(), job_duration);
+        let start = self
+            .find_next_operation_time(current_time, min_part, true)?
+            .start;
+        self.find_next_operation_time(start, job_duration, false)
+    }
+
+    fn execute_job(&mut self, location: usize) {
         // We assume that we are at the current location
-        let location = self.route.sequence[i];
         let mut job_duration = self.time_input.job_durations[location];
-        //let time_windows = &self.time_input.time_windows[location];
-        //let operation_times = self.time_input.operation_times.as_ref().unwrap(); // TODO there should always be operation times here. If we work 24/7, this should be handled in operation times.
-        let mut current_time = self.time_output.end_time;
+        let arrival = self.time_output.end_time;
+        let splits = (
+            self.time_output.job_splits,
+            self.time_output.split_violations,
+        );
+        let mut policy = self.time_input.split_policy(location);
+        let mut current_time = max(self.time_output.end_time, self.release(location));
         let mut job_completed = false;
 
         // it follows a while loop that searches for a time when
@@ -107,6 +244,7 @@ impl<'a> WorkingTimePenalizer<'a> {
         // 5. Minimize the waiting time
         // our solution is feasible, if there are no splits nor lateness.
         let mut must_fit = true;
+        let mut start_time = None;
 
         while !job_completed {
             // We first check if we are within a time window
@@ -116,30 +254,43 @@ impl<'a> WorkingTimePenalizer<'a> {
                 job_duration,
                 must_fit,
             );
-            let maybe_next_time_op = self
-                .time_input
-                .operation_times
-                .as_ref()
-                .unwrap()
-                .find_next_fitting_time(current_time, job_duration, must_fit);
+            let maybe_next_time_op = match must_fit {
+                true => self.find_next_operation_time(current_time, job_duration, true),
+                false => self.find_next_part(current_time, job_duration, policy),
+            };
             match (maybe_next_time_tw, maybe_next_time_op) {
+                (Some(next_time_tw), Some(next_time_op))
+                    if next_time_tw.start != next_time_op.start =>
+                {
+                    // We have to wait until the next fitting time window
+                    current_time = max(next_time_op.start, next_time_tw.start);
+                }
                 (Some(next_time_tw), Some(next_time_op)) => {
-                    if next_time_tw == next_time_op {
+                    // Both start at the same time, so we work until the first of them ends.
+                    let next_time = TimeWindow::new(
+                        next_time_tw.start,
+                        min(next_time_tw.end, next_time_op.end),
+                    );
+                    if next_time.duration() < job_duration
+                        && !policy.allows_part(&next_time, &next_time_op)
+                    {
+                        // The time window ends too early for a part of the job
+                        current_time = next_time.end;
+                    } else {
                         // Tentative time output will take care that there is waiting in between
-                        job_duration -= next_time_tw.duration();
-                        self.add_job(location, next_time_tw);
+                        start_time.get_or_insert(next_time.start);
+                        job_duration -= next_time.duration();
+                        self.add_job(location, next_time);
                         if job_duration == chrono::Duration::zero() {
                             job_completed = true;
                         }
                         current_time = self.time_output.end_time;
-                    } else {
-                        // Otherwise, we have to wait until the next fitting time window
-                        current_time = max(next_time_op.start, next_time_tw.start);
                     }
                 }
                 (None, Some(next_time_op)) => {
                     // There is no time window left, but for operation times, there is
                     // add job will thereby create lateness
+                    start_time.get_or_insert(next_time_op.start);
                     job_duration -= next_time_op.duration();
                     self.add_job(location, next_time_op);
                     if job_duration == chrono::Duration::zero() {
@@ -152,31 +303,109 @@ impl<'a> WorkingTimePenalizer<'a> {
                     // this can only happen if the job is too long,
                     // hence we know that the job has to be split
                     must_fit = false;
-                    self.add_split();
+                    let splittable = policy != SplitPolicy::Forbid
+                        && self
+                            .find_next_part(current_time, job_duration, policy)
+                            .is_some();
+                    if splittable {
+                        self.add_split();
+                    } else {
+                        // the job is split anyway, against its policy
+                        self.time_output.add_split_violation();
+                        policy = SplitPolicy::default();
+                    }
                     current_time = self.time_output.end_time;
                 }
             }
         }
         self.add_lateness(location);
+        // a job without duration starts right away
+        let start_time = start_time.unwrap_or(self.time_output.end_time);
+        self.add_soft_window_penalty(location, start_time);
+        let split = (
+            self.time_output.job_splits,
+            self.time_output.split_violations,
+        ) != splits;
+        self.add_stop(location, arrival, start_time, split);
+        // the services are part of every schedule state, so only those that precedences refer to are kept
+        if self.precedence_index.is_related(location) {
+            self.time_output.add_service(Service {
+                location,
+                start: start_time,
+                end: self.time_output.end_time,
+            });
+        }
+    }
+    /// The earliest start of the job at the location after the minimum lags
+    /// of the jobs it has to follow, as far as they were served earlier on this route.
+    /// Jobs served by other routes cannot be waited for.
+    fn release(&self, location: usize) -> chrono::DateTime<Utc> {
+        self.precedence_index
+            .preceding(location)
+            .iter()
+            .filter_map(|&index| {
+                let precedence = &self.time_input.precedences[index];
+                self.time_output
+                    .services
+                    .iter()
+                    .find(|service| service.location == precedence.before)
+                    .map(|service| precedence.release(service.end))
+            })
+            .max()
+            .unwrap_or(self.time_output.end_time)
+    }
+    fn add_stop(
+        &mut self,
+        location: usize,
+        arrival: chrono::DateTime<Utc>,
+        service_start: chrono::DateTime<Utc>,
+        split: bool,
+    ) {
+        if !self.build_schedule {
+            return;
+        }
+        let time_windows = &self.time_input.time_windows[location];
+        let end_time = self.time_output.end_time;
+        let stop = Stop {
+            location,
+            arrival,
+            service_start,
+            service_end: end_time,
+            departure: end_time,
+            waiting_time: service_start - arrival,
+            lateness: time_windows.lateness(end_time),
+            time_window: time_windows
+                .windows
+                .iter()
+                .position(|window| window.contains(service_start)),
+            split,
+        };
+        self.time_output.add_stop(stop);
     }
-    fn execute_travel(&mut self, i: usize) {
+    fn execute_travel(&mut self, location: usize, next_location: usize) {
         // for add travel, we have to take a look at the travel duration between the current location and the next location
         // also, we have to consider the working times as well as te breaks we do after a certain amount of travel time
-        let location = self.route.sequence[i];
-        let next_location = self.route.sequence[(i + 1) % self.route.sequence.len()];
-        let travel_duration = self.time_input.duration_matrix[location][next_location];
+        let travel_duration = self.time_input.travel_time(location, next_location);
         let mut current_time = self.time_output.end_time;
         let mut remaining_travel_duration = travel_duration;
-        // TODO also consider breaks
         while remaining_travel_duration > chrono::Duration::zero() {
-            let maybe_next_time_op = self
-                .time_input
-                .operation_times
-                .as_ref()
-                .unwrap()
-                .find_next_fitting_time(current_time, remaining_travel_duration, false);
+            // we travel at most until the next break is due
+            let mut next_travel_duration = remaining_travel_duration;
+            if let Some((until_break, break_duration)) = self.time_input.break_rule() {
+                if self.travel_since_break >= until_break {
+                    self.add_break(break_duration);
+                    current_time = self.time_output.end_time;
+                }
+                next_travel_duration =
+                    min(next_travel_duration, until_break - self.travel_since_break);
+            }
+            let maybe_next_time_op =
+                self.find_next_operation_time(current_time, next_travel_duration, false);
             match maybe_next_time_op {
                 Some(next_time_op) => {
+                    if remaining_travel_duration == travel_duration && self.build_schedule {
+                        self.time_output.depart(next_time_op.start);
+                    }
                     remaining_travel_duration -= next_time_op.duration();
                     self.add_travel(next_time_op);
                     current_time = self.time_output.end_time;
@@ -192,12 +421,66 @@ impl<'a> WorkingTimePenalizer<'a> {
 
 pub struct TimePenalizer {
     time_input: TimeInput,
+    precedence_index: PrecedenceIndex,
 }
 
 impl TimePenalizer {
     pub fn new(time_input: TimeInput) -> TimePenalizer {
-        TimePenalizer { time_input }
+        let precedence_index =
+            PrecedenceIndex::new(&time_input.precedences, time_input.job_durations.len());
+        TimePenalizer {
+            time_input,
+            precedence_index,
+        }
     }
+    /// We start at the given start time, or else at the first opening time of the first location,
+    /// but never before the earliest departure of that day.
+    /// From a start depot, we leave just in time to arrive at the first opening time of the first job,
+    /// within the time windows of the depot.
+    /// A route without any location, or whose first location has no time window,
+    /// idles at the earliest opening time of all locations.
+    fn start_time(&self, route: &Route) -> chrono::DateTime<Utc> {
+        let earliest_opening = || {
+            self.time_input
+                .time_windows
+                .iter()
+                .filter_map(|time_windows| time_windows.windows.first())
+                .map(|window| window.start)
+                .min()
+                .unwrap()
+        };
+        let first_opening =
+            |location: usize| self.time_input.time_windows[location].windows.first();
+        let time = self.time_input.start.time.unwrap_or_else(|| {
+            match (route.start_depot(), route.sequence.first()) {
+                (Some(depot), Some(&job)) => match first_opening(job) {
+                    Some(window) => self.depot_departure(
+                        depot,
+                        window.start - self.time_input.duration_matrix[depot][job],
+                    ),
+                    None => {
+                        first_opening(depot).map_or_else(earliest_opening, |window| window.start)
+                    }
+                },
+                _ => route
+                    .first_location()
+                    .and_then(first_opening)
+                    .map_or_else(earliest_opening, |window| window.start),
+            }
+        });
+        self.time_input.start.departure(time)
+    }
+
+    /// The departure from the depot closest to `time` within its time windows:
+    /// the end of the last window that opens before, or the opening of the first window.
+    fn depot_departure(&self, depot: usize, time: chrono::DateTime<Utc>) -> chrono::DateTime<Utc> {
+        let windows = &self.time_input.time_windows[depot].windows;
+        match windows.iter().rev().find(|window| window.start <= time) {
+            Some(window) => time.min(window.end),
+            None => windows.first().map_or(time, |window| window.start),
+        }
+    }
+
     pub fn penalize(&self, route: &Route, build_schedule: bool) -> TimeOutput<Complete> {
         // Here comes the functionalities of the time penalizer
         // We go through the route one location after the other
@@ -207,10 +490,78 @@ impl TimePenalizer {
         // until either the job duration is over or the traveling time is over
         // all inside the operation times.
 
-        // we start at the first opening time of the first location
-        let start_time = self.time_input.time_windows[route.sequence[0]][0].start;
-        let working_time_penalizer =
-            WorkingTimePenalizer::new(&self.time_input, route, start_time, build_schedule);
-        working_time_penalizer.finish_schedule()
+        let working_time_penalizer = WorkingTimePenalizer::new(
+            &self.time_input,
+            &self.precedence_index,
+            route,
+            self.start_time(route),
+            build_schedule,
+        );
+        working_time_penalizer.finish_schedule(0).0
+    }
+
+    /// Like `penalize` without building the schedule,
+    /// but also returns the states before each position of the route.
+    pub fn penalize_with_states(
+        &self,
+        route: &Route,
+    ) -> (TimeOutput<Complete>, Vec<ScheduleState>) {
+        WorkingTimePenalizer::new(
+            &self.time_input,
+            &self.precedence_index,
+            route,
+            self.start_time(route),
+            false,
+        )
+        .finish_schedule(0)
+    }
+
+    /// The precedences violated by the services of the report, which may combine several routes.
+    /// A precedence only applies if both of its jobs are served.
+    pub fn precedence_violations(
+        &self,
+        time_output: &TimeOutput<Complete>,
+    ) -> Vec<PrecedenceViolation> {
+        let precedences = &self.time_input.precedences;
+        if precedences.is_empty() {
+            return vec![];
+        }
+        let mut services = vec![None; self.time_input.job_durations.len()];
+        for service in &time_output.services {
+            services[service.location] = Some(service);
+        }
+        precedences
+            .iter()
+            .enumerate()
+            .filter_map(|(index, precedence)| {
+                let before = services[precedence.before]?;
+                let after = services[precedence.after]?;
+                precedence.violation(index, before, after)
+            })
+            .collect()
+    }
+
+    /// Penalizes a route that only differs from the route of `states` from position `from` on,
+    /// so the schedule only has to be simulated from there.
+    pub fn penalize_from(
+        &self,
+        route: &Route,
+        states: &[ScheduleState],
+        from: usize,
+    ) -> TimeOutput<Complete> {
+        match states.get(from) {
+            // the start time of a route depends on its first job, unless it is given
+            Some(state) if from > 0 || self.time_input.start.time.is_some() => {
+                WorkingTimePenalizer::from_state(
+                    &self.time_input,
+                    &self.precedence_index,
+                    route,
+                    state,
+                )
+                .finish_schedule(from)
+                .0
+            }
+            _ => self.penalize(route, false),
+        }
     }
 }
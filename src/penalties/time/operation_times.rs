@@ -1,104 +1,210 @@
-use std::cmp::min;
+use std::{
+    cmp::{max, min},
+    collections::BTreeMap,
+};
 
-use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use chrono::{
+    DateTime, Datelike, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+    Timelike, Utc,
+};
+use chrono_tz::Tz;
 
 use super::time_windows::TimeWindow;
 
-struct WorkingDays {
-    next_day_cache: [chrono::Weekday; 7],
+const SECONDS_PER_DAY: u32 = 24 * 3600;
+
+/// A period of work starting on a day, in seconds since its local midnight.
+/// An end beyond 86400 is on the next day, so a shift lasts at most 24 hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shift {
+    pub start: u32,
+    pub end: u32,
 }
-impl WorkingDays {
-    fn new(working_days: Vec<chrono::Weekday>) -> WorkingDays {
-        let mut days = [false; 7];
-        for day in working_days {
-            days[day.num_days_from_monday() as usize] = true;
-        }
-        let mut next_day_cache = [chrono::Weekday::Mon; 7];
-        for i in 0..7 {
-            for j in 1..7 {
-                let next_day = (i + j) % 7;
-                if days[next_day] {
-                    next_day_cache[i] = chrono::Weekday::try_from(next_day as u8).unwrap();
-                    break;
-                }
-            }
+
+impl Shift {
+    pub fn new(start: u32, end: u32) -> Shift {
+        assert!(start < SECONDS_PER_DAY && start < end && end <= start + SECONDS_PER_DAY);
+        Shift { start, end }
+    }
+
+    /// The shift between two times of day, which ends on the next day
+    /// if the end is not after the start, e.g. from 22:00 to 06:00.
+    /// With the same start and end, it lasts 24 hours.
+    pub fn between(start: u32, end: u32) -> Shift {
+        match end > start {
+            true => Shift::new(start, end),
+            false => Shift::new(start, end + SECONDS_PER_DAY),
         }
-        WorkingDays { next_day_cache }
     }
-    fn next_working_day(&self, date: NaiveDate) -> NaiveDate {
-        let next_day = self.next_day_cache[date.weekday().num_days_from_monday() as usize];
-        let mut days_to_add = (next_day.num_days_from_monday() as i8
-            - date.weekday().num_days_from_monday() as i8)
-            .rem_euclid(7) as i64;
-        if days_to_add == 0 {
-            days_to_add = 7; // because from monday to next monday is 7 days
+
+    /// The times of day of the start and end, the inverse of `between`.
+    pub fn times(&self) -> (u32, u32) {
+        match self.end > SECONDS_PER_DAY {
+            true => (self.start, self.end - SECONDS_PER_DAY),
+            false => (self.start, self.end),
         }
-        date + chrono::Duration::days(days_to_add)
     }
 }
 
+/// A calendar of the shifts of work, in the local time of `time_zone`.
+/// Each weekday has its own shifts, which are replaced on the dates of exceptions,
+/// e.g. by no shift at all on a holiday.
 pub struct OperationTimes {
-    daily_start: chrono::NaiveTime,
-    daily_end: chrono::NaiveTime,
-    working_days: Option<WorkingDays>,
+    /// The shifts of each weekday, starting on Monday, ordered and not overlapping.
+    weekly: [Vec<Shift>; 7],
+    /// The shifts on specific dates instead of those of their weekday.
+    exceptions: BTreeMap<NaiveDate, Vec<Shift>>,
+    time_zone: Tz,
 }
 
 impl OperationTimes {
+    /// The same hours on all working days, which are all days if not given.
+    /// A shift that ends before it starts ends on the next day.
     pub fn new(
-        daily_start: chrono::NaiveTime,
-        daily_end: chrono::NaiveTime,
+        daily_start: NaiveTime,
+        daily_end: NaiveTime,
         working_days: Option<Vec<chrono::Weekday>>,
     ) -> OperationTimes {
-        assert!(daily_start < daily_end);
+        let shift = Shift::between(
+            daily_start.num_seconds_from_midnight(),
+            daily_end.num_seconds_from_midnight(),
+        );
+        let working_days = working_days.unwrap_or_else(|| {
+            (0..7)
+                .map(|day| chrono::Weekday::try_from(day).unwrap())
+                .collect()
+        });
+        let mut weekly: [Vec<Shift>; 7] = Default::default();
+        for day in working_days {
+            weekly[day.num_days_from_monday() as usize] = vec![shift];
+        }
+        OperationTimes::weekly(weekly)
+    }
 
+    /// Different shifts on each weekday, starting on Monday, with at least one shift a week.
+    pub fn weekly(weekly: [Vec<Shift>; 7]) -> OperationTimes {
+        assert!(weekly.iter().any(|shifts| !shifts.is_empty()));
         OperationTimes {
-            daily_start,
-            daily_end,
-            working_days: working_days.map(|wd| WorkingDays::new(wd)),
+            weekly,
+            exceptions: BTreeMap::new(),
+            time_zone: Tz::UTC,
         }
     }
 
-    pub fn duration(&self) -> chrono::Duration {
-        self.daily_end.signed_duration_since(self.daily_start)
+    /// Replaces the shifts of the weekday on the given dates, no shifts meaning a day off.
+    pub fn with_exceptions(self, exceptions: BTreeMap<NaiveDate, Vec<Shift>>) -> OperationTimes {
+        OperationTimes { exceptions, ..self }
     }
 
-    pub fn start(&self) -> chrono::NaiveTime {
-        self.daily_start
+    /// Interprets the shifts in the given time zone instead of UTC.
+    pub fn in_time_zone(self, time_zone: Tz) -> OperationTimes {
+        OperationTimes { time_zone, ..self }
     }
 
-    pub fn end(&self) -> chrono::NaiveTime {
-        self.daily_end
+    pub fn time_zone(&self) -> Tz {
+        self.time_zone
     }
-    pub fn contains(&self, time: DateTime<Utc>) -> bool {
-        let time = time.time();
-        self.daily_start <= time && time < self.daily_end
+
+    pub fn weekly_shifts(&self) -> &[Vec<Shift>; 7] {
+        &self.weekly
     }
-    pub fn waiting_time(&self, current_time: DateTime<Utc>) -> chrono::Duration {
-        let time = current_time.time();
-        if !self.contains(current_time) {
-            // it is before the daily start
-            if time < self.daily_start {
-                return self.daily_start.signed_duration_since(time);
-            }
-            // it is after the daily end
-            self.start_next_day(current_time) - current_time
-        } else {
-            chrono::Duration::zero()
+
+    pub fn exceptions(&self) -> &BTreeMap<NaiveDate, Vec<Shift>> {
+        &self.exceptions
+    }
+
+    /// The shift of every working day, if all of them have the same single shift.
+    pub fn daily_shift(&self) -> Option<Shift> {
+        let mut days = self.weekly.iter().filter(|shifts| !shifts.is_empty());
+        match days.next()?.as_slice() {
+            &[shift] => days.all(|shifts| shifts == &[shift]).then_some(shift),
+            _ => None,
         }
     }
 
-    pub fn next_day(&self, current_time: DateTime<Utc>) -> NaiveDate {
-        match self.working_days {
-            Some(ref working_days) => {
-                return working_days.next_working_day(current_time.date_naive());
+    /// The weekdays with at least one shift.
+    pub fn working_days(&self) -> Vec<chrono::Weekday> {
+        (0..7)
+            .filter(|&day| !self.weekly[day].is_empty())
+            .map(|day| chrono::Weekday::try_from(day as u8).unwrap())
+            .collect()
+    }
+
+    fn shifts_on(&self, date: NaiveDate) -> &[Shift] {
+        self.exceptions
+            .get(&date)
+            .unwrap_or(&self.weekly[date.weekday().num_days_from_monday() as usize])
+    }
+
+    fn local(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        time.with_timezone(&self.time_zone).naive_local()
+    }
+
+    /// The instant of a local date and time. Of a time that occurs twice when the clocks
+    /// are turned back, it is the first one. A time that is skipped when the clocks
+    /// are turned forward is moved behind the gap, just like a clock on the wall would be.
+    fn instant(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        match self.time_zone.from_local_datetime(&local) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time.with_timezone(&Utc),
+            LocalResult::None => {
+                // the offset before the gap, assuming that the clocks are not changed twice a day
+                let offset = self
+                    .time_zone
+                    .offset_from_utc_datetime(&(local - chrono::Duration::days(1)))
+                    .fix();
+                Utc.from_utc_datetime(&(local - offset))
             }
-            None => { current_time + chrono::Duration::days(1) }.date_naive(),
         }
     }
 
+    fn shift_window(&self, date: NaiveDate, shift: Shift) -> TimeWindow {
+        let midnight = date.and_time(NaiveTime::MIN);
+        let at = |seconds: u32| self.instant(midnight + chrono::Duration::seconds(seconds as i64));
+        TimeWindow::new(at(shift.start), at(shift.end))
+    }
+
+    /// The shifts that end after `time`, in chronological order.
+    /// As there is a shift every week, this never runs out.
+    fn windows_from(&self, time: DateTime<Utc>) -> impl Iterator<Item = TimeWindow> + '_ {
+        // a shift of the day before may last until after midnight
+        self.local(time)
+            .date()
+            .pred_opt()
+            .unwrap()
+            .iter_days()
+            .flat_map(move |date| {
+                self.shifts_on(date)
+                    .iter()
+                    .map(move |&shift| self.shift_window(date, shift))
+            })
+            .filter(move |window| window.start < window.end && window.end > time)
+    }
+
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.windows_from(time)
+            .next()
+            .is_some_and(|window| window.start <= time)
+    }
+
+    pub fn waiting_time(&self, current_time: DateTime<Utc>) -> chrono::Duration {
+        let window = self.windows_from(current_time).next().unwrap();
+        max(window.start - current_time, chrono::Duration::zero())
+    }
+
+    /// The local date of the next day with a shift.
+    pub fn next_day(&self, current_time: DateTime<Utc>) -> NaiveDate {
+        self.local(current_time)
+            .date()
+            .iter_days()
+            .skip(1)
+            .find(|&date| !self.shifts_on(date).is_empty())
+            .unwrap()
+    }
+
     pub fn start_next_day(&self, current_time: DateTime<Utc>) -> DateTime<Utc> {
         let next_day = self.next_day(current_time);
-        Utc.from_utc_datetime(&next_day.and_time(self.daily_start))
+        self.shift_window(next_day, self.shifts_on(next_day)[0])
+            .start
     }
 
     pub fn find_next_fitting_time(
@@ -107,31 +213,28 @@ impl OperationTimes {
         job_duration: chrono::Duration,
         must_fit: bool,
     ) -> Option<TimeWindow> {
-        let waiting_time = self.waiting_time(current_time);
-        let start_time = current_time + waiting_time;
-        let end_time = min(
-            start_time + job_duration,
-            // the following unwrap is likely to be safe because the datetime is within the representable range for a DateTime
-            start_time.with_time(self.daily_end).unwrap(),
-        );
-        let result_tw = TimeWindow::new(start_time, end_time);
-        match must_fit {
-            false => Some(result_tw),
-            true => {
-                // If the job duration is bigger than the proposed time window,
-                // we have to wait until the next day and start fresh.
-                // If it still does not fit, we return None.
-                if result_tw.duration() == job_duration {
-                    return Some(result_tw);
-                }
-                if job_duration > self.duration() {
-                    return None;
-                }
-                let start_time = self.start_next_day(current_time);
-                let end_time = start_time + job_duration;
-                return Some(TimeWindow::new(start_time, end_time));
-            }
+        let mut windows = self.windows_from(current_time);
+        let window = windows.next().unwrap();
+        let start_time = max(current_time, window.start);
+        let result_tw = TimeWindow::new(start_time, min(start_time + job_duration, window.end));
+        if !must_fit || result_tw.duration() == job_duration {
+            return Some(result_tw);
         }
+        // If the job does not fit into the rest of this shift, it starts fresh with a later one.
+        // After the last exception, each weekday comes by within a week,
+        // so if the job did not fit until then, it never does.
+        let last_date = self
+            .exceptions
+            .keys()
+            .next_back()
+            .map_or(window.start, |&date| {
+                max(window.start, self.instant(date.and_time(NaiveTime::MIN)))
+            })
+            + chrono::Duration::weeks(1);
+        windows
+            .take_while(|window| window.start <= last_date)
+            .find(|window| window.duration() >= job_duration)
+            .map(|window| TimeWindow::new(window.start, window.start + job_duration))
     }
 }
 
@@ -149,14 +252,9 @@ mod tests {
             None,
         );
         assert_eq!(
-            operation_times.start(),
-            NaiveTime::from_hms_opt(8, 0, 0).unwrap()
-        );
-        assert_eq!(
-            operation_times.end(),
-            NaiveTime::from_hms_opt(16, 0, 0).unwrap()
+            operation_times.daily_shift(),
+            Some(Shift::new(28800, 57600))
         );
-        assert_eq!(operation_times.duration(), chrono::Duration::hours(8));
         assert!(!operation_times.contains(Utc.with_ymd_and_hms(2021, 1, 1, 7, 0, 0).unwrap()));
         assert!(operation_times.contains(Utc.with_ymd_and_hms(2021, 1, 1, 8, 0, 0).unwrap()));
         assert!(operation_times.contains(Utc.with_ymd_and_hms(2021, 1, 1, 10, 0, 0).unwrap()));
@@ -273,4 +371,220 @@ mod tests {
         let result = operation_times.find_next_fitting_time(current_time, job_duration, true);
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_calendar() {
+        let hours = |start: u32, end: u32| Shift::new(start * 3600, end * 3600);
+        // 07:00 to 16:00 from Monday to Thursday, 07:00 to 12:00 on Friday
+        let mut weekly: [Vec<Shift>; 7] = Default::default();
+        for shifts in weekly.iter_mut().take(4) {
+            *shifts = vec![hours(7, 16)];
+        }
+        weekly[4] = vec![hours(7, 12)];
+        let operation_times = OperationTimes::weekly(weekly).with_exceptions(BTreeMap::from([
+            // a holiday on Thursday, 1 May 2025
+            (NaiveDate::from_ymd_opt(2025, 5, 1).unwrap(), vec![]),
+            // two shifts on an extra Saturday
+            (
+                NaiveDate::from_ymd_opt(2025, 5, 3).unwrap(),
+                vec![hours(8, 10), hours(14, 18)],
+            ),
+        ]));
+        assert_eq!(operation_times.daily_shift(), None);
+        assert!(operation_times.contains(Utc.with_ymd_and_hms(2025, 4, 29, 15, 0, 0).unwrap()));
+        assert!(!operation_times.contains(Utc.with_ymd_and_hms(2025, 4, 25, 13, 0, 0).unwrap()));
+        // Wednesday evening waits for Friday, since Thursday is a holiday
+        assert_eq!(
+            operation_times.start_next_day(Utc.with_ymd_and_hms(2025, 4, 30, 17, 0, 0).unwrap()),
+            Utc.with_ymd_and_hms(2025, 5, 2, 7, 0, 0).unwrap()
+        );
+        // Friday ends at noon, then the extra Saturday starts
+        assert_eq!(
+            operation_times.find_next_fitting_time(
+                Utc.with_ymd_and_hms(2025, 5, 2, 11, 0, 0).unwrap(),
+                chrono::Duration::hours(2),
+                false
+            ),
+            Some(TimeWindow::new(
+                Utc.with_ymd_and_hms(2025, 5, 2, 11, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 5, 2, 12, 0, 0).unwrap()
+            ))
+        );
+        assert_eq!(
+            operation_times.waiting_time(Utc.with_ymd_and_hms(2025, 5, 2, 12, 0, 0).unwrap()),
+            chrono::Duration::hours(20)
+        );
+        // three hours do not fit into the first shift on Saturday, but into the second
+        assert_eq!(
+            operation_times.find_next_fitting_time(
+                Utc.with_ymd_and_hms(2025, 5, 3, 8, 0, 0).unwrap(),
+                chrono::Duration::hours(3),
+                true
+            ),
+            Some(TimeWindow::new(
+                Utc.with_ymd_and_hms(2025, 5, 3, 14, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 5, 3, 17, 0, 0).unwrap()
+            ))
+        );
+        // six hours only fit into a shift from Monday to Thursday
+        assert_eq!(
+            operation_times.find_next_fitting_time(
+                Utc.with_ymd_and_hms(2025, 5, 2, 7, 0, 0).unwrap(),
+                chrono::Duration::hours(6),
+                true
+            ),
+            Some(TimeWindow::new(
+                Utc.with_ymd_and_hms(2025, 5, 5, 7, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 5, 5, 13, 0, 0).unwrap()
+            ))
+        );
+        // and ten hours never fit
+        assert!(operation_times
+            .find_next_fitting_time(
+                Utc.with_ymd_and_hms(2025, 5, 2, 7, 0, 0).unwrap(),
+                chrono::Duration::hours(10),
+                true
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_overnight_shifts() {
+        let at = |day, hour| Utc.with_ymd_and_hms(2025, 1, day, hour, 0, 0).unwrap();
+        // a night crew from 22:00 to 06:00, starting on Sunday, 5 January 2025
+        let operation_times = OperationTimes::new(
+            NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            Some(vec![chrono::Weekday::Sun]),
+        );
+        assert_eq!(
+            operation_times.daily_shift(),
+            Some(Shift::new(79200, 108000))
+        );
+        assert_eq!(Shift::new(79200, 108000).times(), (79200, 21600));
+        assert!(!operation_times.contains(at(5, 21)));
+        assert!(operation_times.contains(at(5, 23)));
+        // the shift of Sunday goes on into the next week
+        assert!(operation_times.contains(at(6, 5)));
+        assert!(!operation_times.contains(at(6, 6)));
+        assert_eq!(
+            operation_times.waiting_time(at(6, 6)),
+            chrono::Duration::hours(6 * 24 + 16)
+        );
+        assert_eq!(operation_times.start_next_day(at(6, 3)), at(12, 22));
+        assert_eq!(
+            operation_times.find_next_fitting_time(at(5, 23), chrono::Duration::hours(3), false),
+            Some(TimeWindow::new(at(5, 23), at(6, 2)))
+        );
+        assert_eq!(
+            operation_times.find_next_fitting_time(at(6, 4), chrono::Duration::hours(3), false),
+            Some(TimeWindow::new(at(6, 4), at(6, 6)))
+        );
+        // three hours do not fit into the last two of the night, so the job waits a week
+        assert_eq!(
+            operation_times.find_next_fitting_time(at(6, 4), chrono::Duration::hours(3), true),
+            Some(TimeWindow::new(at(12, 22), at(13, 1)))
+        );
+
+        // from Monday to Friday, the night of Friday ends on Saturday morning
+        let operation_times = OperationTimes::new(
+            NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            Some(vec![
+                chrono::Weekday::Mon,
+                chrono::Weekday::Tue,
+                chrono::Weekday::Wed,
+                chrono::Weekday::Thu,
+                chrono::Weekday::Fri,
+            ]),
+        );
+        assert!(operation_times.contains(at(11, 5)));
+        assert!(!operation_times.contains(at(12, 5)));
+        assert_eq!(
+            operation_times.waiting_time(at(11, 7)),
+            chrono::Duration::hours(2 * 24 + 15)
+        );
+        // and the night of Sunday is off
+        assert!(!operation_times.contains(at(6, 3)));
+        assert!(operation_times.contains(at(7, 3)));
+    }
+
+    #[test]
+    fn test_time_zone() {
+        // 08:00 to 17:00 from Monday to Friday in Berlin
+        let operation_times = OperationTimes::new(
+            NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+            Some(vec![
+                chrono::Weekday::Mon,
+                chrono::Weekday::Tue,
+                chrono::Weekday::Wed,
+                chrono::Weekday::Thu,
+                chrono::Weekday::Fri,
+            ]),
+        )
+        .in_time_zone(chrono_tz::Europe::Berlin);
+        // in winter, Berlin is one hour ahead of UTC
+        assert!(!operation_times.contains(Utc.with_ymd_and_hms(2025, 1, 6, 6, 59, 0).unwrap()));
+        assert!(operation_times.contains(Utc.with_ymd_and_hms(2025, 1, 6, 7, 0, 0).unwrap()));
+        assert!(!operation_times.contains(Utc.with_ymd_and_hms(2025, 1, 6, 16, 0, 0).unwrap()));
+        // in summer, it is two hours ahead
+        assert!(operation_times.contains(Utc.with_ymd_and_hms(2025, 7, 7, 6, 0, 0).unwrap()));
+        assert!(!operation_times.contains(Utc.with_ymd_and_hms(2025, 7, 7, 15, 0, 0).unwrap()));
+        assert_eq!(
+            operation_times.waiting_time(Utc.with_ymd_and_hms(2025, 7, 7, 5, 0, 0).unwrap()),
+            chrono::Duration::hours(1)
+        );
+        // 23:30 UTC on Sunday is already Monday in Berlin
+        assert_eq!(
+            operation_times.waiting_time(Utc.with_ymd_and_hms(2025, 1, 5, 23, 30, 0).unwrap()),
+            chrono::Duration::minutes(450)
+        );
+        // 23:30 UTC on Friday is Saturday in Berlin, so the next working day is Monday
+        assert_eq!(
+            operation_times.start_next_day(Utc.with_ymd_and_hms(2025, 1, 10, 23, 30, 0).unwrap()),
+            Utc.with_ymd_and_hms(2025, 1, 13, 7, 0, 0).unwrap()
+        );
+        // the clocks are turned forward on Sunday, 30 March 2025, and back on Sunday, 26 October 2025
+        assert_eq!(
+            operation_times.start_next_day(Utc.with_ymd_and_hms(2025, 3, 28, 16, 0, 0).unwrap()),
+            Utc.with_ymd_and_hms(2025, 3, 31, 6, 0, 0).unwrap()
+        );
+        assert_eq!(
+            operation_times.start_next_day(Utc.with_ymd_and_hms(2025, 10, 24, 15, 0, 0).unwrap()),
+            Utc.with_ymd_and_hms(2025, 10, 27, 7, 0, 0).unwrap()
+        );
+        // the working day ends at 17:00 local time
+        assert_eq!(
+            operation_times.find_next_fitting_time(
+                Utc.with_ymd_and_hms(2025, 7, 7, 14, 0, 0).unwrap(),
+                chrono::Duration::hours(2),
+                false
+            ),
+            Some(TimeWindow::new(
+                Utc.with_ymd_and_hms(2025, 7, 7, 14, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 7, 7, 15, 0, 0).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_time_zone_gap() {
+        // work starting at 02:30 on the night the clocks are turned forward starts at 03:30
+        let operation_times = OperationTimes::new(
+            NaiveTime::from_hms_opt(2, 30, 0).unwrap(),
+            NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            None,
+        )
+        .in_time_zone(chrono_tz::Europe::Berlin);
+        assert_eq!(
+            operation_times.start_next_day(Utc.with_ymd_and_hms(2025, 3, 29, 12, 0, 0).unwrap()),
+            Utc.with_ymd_and_hms(2025, 3, 30, 1, 30, 0).unwrap()
+        );
+        // and at 02:30 on the first of the two at the night they are turned back
+        assert_eq!(
+            operation_times.start_next_day(Utc.with_ymd_and_hms(2025, 10, 25, 12, 0, 0).unwrap()),
+            Utc.with_ymd_and_hms(2025, 10, 26, 0, 30, 0).unwrap()
+        );
+    }
 }
@@ -0,0 +1,157 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::time_output::Service;
+
+/// The job at `after` may only start `min_lag` after the job at `before` ends,
+/// and, with a maximum lag, at most `max_lag` after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Precedence {
+    pub before: usize,
+    pub after: usize,
+    pub min_lag: Duration,
+    pub max_lag: Option<Duration>,
+}
+
+impl Precedence {
+    /// The earliest start of the later job if the earlier one ends at the given time.
+    pub fn release(&self, before_end: DateTime<Utc>) -> DateTime<Utc> {
+        before_end + self.min_lag
+    }
+
+    /// How the services of both jobs violate the precedence, None if they keep it.
+    pub fn violation(
+        &self,
+        index: usize,
+        before: &Service,
+        after: &Service,
+    ) -> Option<PrecedenceViolation> {
+        let release = self.release(before.end);
+        let early = (release - after.start).max(Duration::zero());
+        let late = self.max_lag.map_or(Duration::zero(), |max_lag| {
+            (after.start - (before.end + max_lag)).max(Duration::zero())
+        });
+        (early > Duration::zero() || late > Duration::zero()).then_some(PrecedenceViolation {
+            precedence: index,
+            before: self.before,
+            after: self.after,
+            early,
+            late,
+        })
+    }
+}
+
+/// The precedences by the jobs they relate, so that a schedule only keeps the services it needs
+/// and finds the precedences of a job without going through all of them.
+#[derive(Debug, Clone)]
+pub struct PrecedenceIndex {
+    /// The indices of the precedences the job at each location has to follow.
+    preceding: Vec<Vec<usize>>,
+    /// Whether some precedence relates the job at each location.
+    related: Vec<bool>,
+}
+
+impl PrecedenceIndex {
+    pub fn new(precedences: &[Precedence], n: usize) -> PrecedenceIndex {
+        let mut preceding = vec![vec![]; n];
+        let mut related = vec![false; n];
+        for (index, precedence) in precedences.iter().enumerate() {
+            preceding[precedence.after].push(index);
+            related[precedence.before] = true;
+            related[precedence.after] = true;
+        }
+        PrecedenceIndex { preceding, related }
+    }
+
+    /// The indices of the precedences the job at the location has to follow.
+    pub fn preceding(&self, location: usize) -> &[usize] {
+        &self.preceding[location]
+    }
+
+    /// Whether the service of the job at the location matters to any precedence.
+    pub fn is_related(&self, location: usize) -> bool {
+        self.related[location]
+    }
+}
+
+/// How far the later job of a precedence starts too early or too late.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrecedenceViolation {
+    /// The index of the precedence.
+    pub precedence: usize,
+    pub before: usize,
+    pub after: usize,
+    /// How long before the minimum lag has passed the later job starts,
+    /// including the time it starts before the earlier one ends.
+    pub early: Duration,
+    /// How long after the maximum lag has passed the later job starts.
+    pub late: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_violation() {
+        let at = |h| Utc.with_ymd_and_hms(2021, 1, 1, h, 0, 0).unwrap();
+        let service = |location, start, end| Service {
+            location,
+            start: at(start),
+            end: at(end),
+        };
+        // the survey has to be at least two and at most six hours before the installation
+        let precedence = Precedence {
+            before: 0,
+            after: 1,
+            min_lag: Duration::hours(2),
+            max_lag: Some(Duration::hours(6)),
+        };
+        assert_eq!(precedence.release(at(10)), at(12));
+        assert_eq!(
+            precedence.violation(3, &service(0, 8, 10), &service(1, 12, 13)),
+            None
+        );
+        assert_eq!(
+            precedence.violation(3, &service(0, 8, 10), &service(1, 11, 12)),
+            Some(PrecedenceViolation {
+                precedence: 3,
+                before: 0,
+                after: 1,
+                early: Duration::hours(1),
+                late: Duration::zero(),
+            })
+        );
+        assert_eq!(
+            precedence
+                .violation(3, &service(0, 8, 10), &service(1, 18, 19))
+                .map(|violation| violation.late),
+            Some(Duration::hours(2))
+        );
+        // the other way round
+        assert_eq!(
+            precedence
+                .violation(3, &service(0, 8, 10), &service(1, 6, 7))
+                .map(|violation| violation.early),
+            Some(Duration::hours(6))
+        );
+    }
+
+    #[test]
+    fn test_precedence_index() {
+        let precedence = |before, after| Precedence {
+            before,
+            after,
+            min_lag: Duration::zero(),
+            max_lag: None,
+        };
+        let index =
+            PrecedenceIndex::new(&[precedence(0, 2), precedence(1, 2), precedence(2, 3)], 5);
+        assert_eq!(index.preceding(2), &[0, 1]);
+        assert_eq!(index.preceding(3), &[2]);
+        assert!(index.preceding(0).is_empty());
+        assert!(index.is_related(3));
+        assert!(!index.is_related(4));
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::time_windows::TimeWindow;
+
+/// A piecewise-linear cost of deviating from a preferred time window.
+/// The cost is zero without deviation and interpolated linearly between the given
+/// (deviation, cost) points. Beyond the last point, it keeps increasing at the slope of the last piece.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostFunction {
+    points: Vec<(Duration, f64)>,
+}
+
+impl CostFunction {
+    /// Creates a cost function through the given points.
+    /// Panics unless the deviations increase and the costs do not decrease, starting at zero.
+    pub fn new(points: Vec<(Duration, f64)>) -> CostFunction {
+        let mut previous = (Duration::zero(), 0.0);
+        for &(deviation, cost) in &points {
+            assert!(previous.0 < deviation && previous.1 <= cost);
+            previous = (deviation, cost);
+        }
+        CostFunction { points }
+    }
+
+    /// A cost that grows by `cost` for every hour of deviation.
+    pub fn linear(cost: f64) -> CostFunction {
+        CostFunction::new(vec![(Duration::hours(1), cost)])
+    }
+
+    pub fn points(&self) -> &[(Duration, f64)] {
+        &self.points
+    }
+
+    pub fn cost(&self, deviation: Duration) -> f64 {
+        if deviation <= Duration::zero() {
+            return 0.0;
+        }
+        let mut previous = (Duration::zero(), 0.0);
+        for (i, &point) in self.points.iter().enumerate() {
+            // the last piece goes on forever
+            if deviation <= point.0 || i == self.points.len() - 1 {
+                let slope = (point.1 - previous.1) / (point.0 - previous.0).num_seconds() as f64;
+                return previous.1 + slope * (deviation - previous.0).num_seconds() as f64;
+            }
+            previous = point;
+        }
+        0.0
+    }
+}
+
+/// A preferred time window of a location. Unlike its time windows, which the vehicle waits for,
+/// the job starts as soon as possible and pays for starting early or ending late.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoftWindow {
+    pub window: TimeWindow,
+    pub earliness: CostFunction,
+    pub lateness: CostFunction,
+}
+
+impl SoftWindow {
+    /// The penalty of a job at the location that starts and ends at the given times.
+    pub fn penalty(&self, location: usize, start: DateTime<Utc>, end: DateTime<Utc>) -> JobPenalty {
+        let earliness = (self.window.start - start).max(Duration::zero());
+        let lateness = (end - self.window.end).max(Duration::zero());
+        JobPenalty {
+            location,
+            earliness,
+            lateness,
+            cost: self.earliness.cost(earliness) + self.lateness.cost(lateness),
+        }
+    }
+}
+
+/// How far a job misses the preferred time window of its location, and what it costs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobPenalty {
+    pub location: usize,
+    pub earliness: Duration,
+    pub lateness: Duration,
+    pub cost: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_cost_function() {
+        let linear = CostFunction::linear(2.0);
+        assert_eq!(linear.cost(Duration::zero()), 0.0);
+        assert_eq!(linear.cost(Duration::minutes(30)), 1.0);
+        assert_eq!(linear.cost(Duration::hours(3)), 6.0);
+        // free for a quarter of an hour, then expensive
+        let piecewise = CostFunction::new(vec![
+            (Duration::minutes(15), 0.0),
+            (Duration::minutes(45), 10.0),
+        ]);
+        assert_eq!(piecewise.cost(Duration::minutes(10)), 0.0);
+        assert_eq!(piecewise.cost(Duration::minutes(30)), 5.0);
+        assert_eq!(piecewise.cost(Duration::minutes(75)), 20.0);
+        assert_eq!(CostFunction::new(vec![]).cost(Duration::hours(1)), 0.0);
+    }
+
+    #[test]
+    fn test_penalty() {
+        let soft_window = SoftWindow {
+            window: TimeWindow::new(
+                Utc.with_ymd_and_hms(2021, 1, 1, 10, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2021, 1, 1, 12, 0, 0).unwrap(),
+            ),
+            earliness: CostFunction::linear(1.0),
+            lateness: CostFunction::linear(3.0),
+        };
+        let at = |h| Utc.with_ymd_and_hms(2021, 1, 1, h, 0, 0).unwrap();
+        assert_eq!(
+            soft_window.penalty(4, at(10), at(12)),
+            JobPenalty {
+                location: 4,
+                earliness: Duration::zero(),
+                lateness: Duration::zero(),
+                cost: 0.0
+            }
+        );
+        // a long job can be both early and late
+        assert_eq!(
+            soft_window.penalty(4, at(8), at(13)),
+            JobPenalty {
+                location: 4,
+                earliness: Duration::hours(2),
+                lateness: Duration::hours(1),
+                cost: 5.0
+            }
+        );
+    }
+}
@@ -1,16 +1,177 @@
+use std::{
+    cmp::max,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use chrono::{Datelike, TimeZone};
+
+use crate::input::error::InputError;
+
 use super::{
-    operation_times::OperationTimes,
+    operation_times::{OperationTimes, Shift},
+    precedences::Precedence,
+    soft_windows::{CostFunction, SoftWindow},
     time_windows::{TimeWindow, TimeWindows},
 };
-/// input for time window constraints
 
+/// The options of `solve` that make up the time model, before they are validated.
+#[derive(Debug, Clone, Default)]
+pub struct RawTimeInput {
+    pub duration_matrix: Option<Vec<Vec<u64>>>,
+    pub job_durations: Option<Vec<u64>>,
+    pub time_windows: Option<Vec<RawTimeWindows>>,
+    pub operation_times: Option<(u64, u64)>,
+    pub working_days: Option<Vec<bool>>,
+    pub travel_duration_until_break: Option<u64>,
+    pub break_duration: Option<u64>,
+    pub time_zone: Option<String>,
+    pub weekly_operation_times: Option<Vec<Vec<(u64, u64)>>>,
+    pub operation_time_exceptions: Option<BTreeMap<String, Vec<(u64, u64)>>>,
+    pub horizon_end: Option<u64>,
+    pub max_route_duration: Option<u64>,
+    pub soft_limits: bool,
+    pub start_time: Option<u64>,
+    pub earliest_departure: Option<u64>,
+    pub soft_time_windows: Option<Vec<RawSoftWindow>>,
+    pub split_policies: Option<Vec<String>>,
+    pub min_split_durations: Option<Vec<u64>>,
+    pub precedences: Option<Vec<RawPrecedence>>,
+}
+
+/// input for time window constraints
+#[derive(Default)]
 pub struct TimeInput {
     pub duration_matrix: Vec<Vec<chrono::Duration>>,
     pub job_durations: Vec<chrono::Duration>,
     pub time_windows: Vec<TimeWindows>,
     pub operation_times: Option<OperationTimes>,
-    pub travel_duration_until_break: Option<u64>,
-    pub break_duration: Option<u64>,
+    pub travel_duration_until_break: Option<chrono::Duration>,
+    pub break_duration: Option<chrono::Duration>,
+    pub limits: Limits,
+    pub start: Start,
+    /// The preferred time windows of the locations, empty if no location has one.
+    pub soft_windows: Vec<Option<SoftWindow>>,
+    /// How the jobs of the locations may be split, empty if all of them may be split anywhere.
+    pub split_policies: Vec<SplitPolicy>,
+    /// Pairs of jobs that have to be served one after the other.
+    pub precedences: Vec<Precedence>,
+}
+
+/// How a job that does not fit into the operation times or its time windows may be split.
+/// Splitting against the policy is a violation rather than a job split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitPolicy {
+    /// The job may be split anywhere, into parts of at least `min_part`.
+    Allow { min_part: chrono::Duration },
+    /// The job must not be split at all.
+    Forbid,
+    /// The job may only be split at the end of a shift, into parts of at least `min_part`.
+    AcrossDays { min_part: chrono::Duration },
+}
+
+impl Default for SplitPolicy {
+    fn default() -> Self {
+        SplitPolicy::Allow {
+            min_part: chrono::Duration::zero(),
+        }
+    }
+}
+
+impl SplitPolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SplitPolicy::Allow { .. } => "allow",
+            SplitPolicy::Forbid => "forbid",
+            SplitPolicy::AcrossDays { .. } => "across_days",
+        }
+    }
+
+    pub fn min_part(&self) -> chrono::Duration {
+        match *self {
+            SplitPolicy::Allow { min_part } | SplitPolicy::AcrossDays { min_part } => min_part,
+            SplitPolicy::Forbid => chrono::Duration::zero(),
+        }
+    }
+
+    /// Whether a part of a job may be worked off in `part`, leaving the rest for later.
+    /// `operation_time` is the operation time the part starts in, up to the end of its shift.
+    pub fn allows_part(&self, part: &TimeWindow, operation_time: &TimeWindow) -> bool {
+        match self {
+            SplitPolicy::Allow { min_part } => part.duration() >= *min_part,
+            SplitPolicy::Forbid => true,
+            SplitPolicy::AcrossDays { min_part } => {
+                part.duration() >= *min_part && part.end == operation_time.end
+            }
+        }
+    }
+}
+
+/// When the routes start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Start {
+    /// When the vehicles are available at the start of their routes.
+    /// If not given, a route starts when its first location opens.
+    pub time: Option<chrono::DateTime<chrono::Utc>>,
+    /// The earliest time of day at which a route departs.
+    pub earliest_departure: Option<chrono::NaiveTime>,
+    /// The time zone of the earliest departure.
+    pub time_zone: chrono_tz::Tz,
+}
+
+impl Start {
+    /// When a route departs that could depart at `time`:
+    /// not before the earliest departure on that day.
+    pub fn departure(&self, time: chrono::DateTime<chrono::Utc>) -> chrono::DateTime<chrono::Utc> {
+        let Some(earliest_departure) = self.earliest_departure else {
+            return time;
+        };
+        let date = time.with_timezone(&self.time_zone).date_naive();
+        // a departure that is skipped when the clocks are turned forward does not hold anyone back
+        match self
+            .time_zone
+            .from_local_datetime(&date.and_time(earliest_departure))
+            .earliest()
+        {
+            Some(departure) => time.max(departure.with_timezone(&chrono::Utc)),
+            None => time,
+        }
+    }
+}
+
+/// Limits that every route has to keep.
+/// They are checked once a route is scheduled, so a route that exceeds them is penalized,
+/// but not cut off: its jobs are still scheduled after the horizon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Limits {
+    /// End of the planning horizon, by which every route has to be finished.
+    pub horizon_end: Option<chrono::DateTime<chrono::Utc>>,
+    /// Maximum duration of a route, from its start to its end.
+    pub max_duration: Option<chrono::Duration>,
+    /// Whether exceeding the limits is a soft excess instead of overtime,
+    /// which the default objective ranks above everything else.
+    pub soft: bool,
+}
+
+impl Limits {
+    /// By how much a route from `start_time` that takes `duration` exceeds the limits,
+    /// summed up over both limits.
+    pub fn excess(
+        &self,
+        start_time: chrono::DateTime<chrono::Utc>,
+        duration: chrono::Duration,
+    ) -> chrono::Duration {
+        let beyond_horizon = self
+            .horizon_end
+            .map_or(chrono::Duration::zero(), |horizon_end| {
+                start_time + duration - horizon_end
+            });
+        let too_long = self
+            .max_duration
+            .map_or(chrono::Duration::zero(), |max_duration| {
+                duration - max_duration
+            });
+        beyond_horizon.max(chrono::Duration::zero()) + too_long.max(chrono::Duration::zero())
+    }
 }
 
 impl TimeInput {
@@ -23,141 +184,629 @@ impl TimeInput {
     //     }
     // }
 
+    pub fn split_policy(&self, location: usize) -> SplitPolicy {
+        self.split_policies
+            .get(location)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn soft_window(&self, location: usize) -> Option<&SoftWindow> {
+        self.soft_windows.get(location)?.as_ref()
+    }
+
     pub fn travel_time(&self, from: usize, to: usize) -> chrono::Duration {
         self.duration_matrix[from][to]
     }
+
+    /// Returns how long one may travel before a break and how long the break takes,
+    /// if breaks have to be made at all.
+    pub fn break_rule(&self) -> Option<(chrono::Duration, chrono::Duration)> {
+        match (self.travel_duration_until_break, self.break_duration) {
+            (Some(until_break), Some(break_duration))
+                if until_break > chrono::Duration::zero()
+                    && break_duration > chrono::Duration::zero() =>
+            {
+                Some((until_break, break_duration))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Converts a number of seconds into a duration, if it is representable.
+pub(crate) fn seconds(
+    field: &'static str,
+    location: Option<usize>,
+    value: u64,
+) -> Result<chrono::Duration, InputError> {
+    i64::try_from(value)
+        .ok()
+        .and_then(chrono::Duration::try_seconds)
+        .ok_or(InputError::DurationOutOfRange {
+            field,
+            location,
+            value,
+        })
+}
+
+fn timestamp(
+    field: &'static str,
+    location: Option<usize>,
+    value: u64,
+) -> Result<chrono::DateTime<chrono::Utc>, InputError> {
+    i64::try_from(value)
+        .ok()
+        .and_then(|seconds| chrono::DateTime::from_timestamp(seconds, 0))
+        .ok_or(InputError::TimestampOutOfRange {
+            field,
+            location,
+            value,
+        })
+}
+
+/// The (start, end) timestamps of the time windows of a location,
+/// `None` or empty if it can be visited at any time.
+pub type RawTimeWindows = Option<Vec<(u64, u64)>>;
+
+/// The preferred (start, end) timestamps of a location with the (deviation in seconds, cost) points
+/// of the costs for starting before and ending after them, `None` if it has no preference.
+pub type RawSoftWindow = Option<(u64, u64, Vec<(u64, f64)>, Vec<(u64, f64)>)>;
+
+/// A job that has to be served before another one, (before, after, minimum lag, maximum lag),
+/// with the lags in seconds between the end of the first and the start of the second job.
+pub type RawPrecedence = (usize, usize, Option<u64>, Option<u64>);
+
+fn transform_precedence(
+    index: usize,
+    &(before, after, min_lag, max_lag): &RawPrecedence,
+) -> Result<Precedence, InputError> {
+    let invalid = |reason| InputError::InvalidPrecedence {
+        precedence: index,
+        reason,
+    };
+    if before == after {
+        return Err(invalid("relates a job to itself"));
+    }
+    let min_lag = min_lag
+        .map(|x| seconds("precedences", Some(index), x))
+        .transpose()?
+        .unwrap_or_default();
+    let max_lag = max_lag
+        .map(|x| seconds("precedences", Some(index), x))
+        .transpose()?;
+    if max_lag.is_some_and(|max_lag| max_lag < min_lag) {
+        return Err(invalid("has a maximum lag below its minimum lag"));
+    }
+    Ok(Precedence {
+        before,
+        after,
+        min_lag,
+        max_lag,
+    })
+}
+
+/// The index of the first precedence whose later job already has to precede its earlier one
+/// through the precedences before it, so that no schedule can satisfy all of them.
+fn closing_precedence(precedences: &[Precedence]) -> Option<usize> {
+    let precedes = |index: usize, from: usize, to: usize| {
+        let mut reached = BTreeSet::from([from]);
+        let mut pending = vec![from];
+        while let Some(job) = pending.pop() {
+            for precedence in &precedences[..index] {
+                if precedence.before == job && reached.insert(precedence.after) {
+                    pending.push(precedence.after);
+                }
+            }
+        }
+        reached.contains(&to)
+    };
+    precedences
+        .iter()
+        .enumerate()
+        .position(|(index, precedence)| precedes(index, precedence.after, precedence.before))
+}
+
+fn transform_split_policies(
+    split_policies: Option<Vec<String>>,
+    min_split_durations: Option<Vec<u64>>,
+) -> Result<Vec<SplitPolicy>, InputError> {
+    let n = max(
+        split_policies.as_ref().map_or(0, Vec::len),
+        min_split_durations.as_ref().map_or(0, Vec::len),
+    );
+    (0..n)
+        .map(|location| {
+            let min_part = min_split_durations
+                .as_ref()
+                .and_then(|durations| durations.get(location))
+                .map(|&x| seconds("min_split_durations", Some(location), x))
+                .transpose()?
+                .unwrap_or_default();
+            let name = split_policies
+                .as_ref()
+                .and_then(|policies| policies.get(location))
+                .map_or("allow", String::as_str);
+            match name {
+                "allow" => Ok(SplitPolicy::Allow { min_part }),
+                "forbid" => Ok(SplitPolicy::Forbid),
+                "across_days" => Ok(SplitPolicy::AcrossDays { min_part }),
+                _ => Err(InputError::InvalidOption {
+                    field: "split_policies",
+                    value: name.to_string(),
+                }),
+            }
+        })
+        .collect()
+}
+
+fn transform_cost_function(
+    location: usize,
+    points: &[(u64, f64)],
+) -> Result<CostFunction, InputError> {
+    let invalid = InputError::InvalidSoftTimeWindow {
+        location,
+        reason: "has cost points that are not increasing from zero",
+    };
+    let mut previous = (0, 0.0);
+    let mut cost_points = Vec::with_capacity(points.len());
+    for &(deviation, cost) in points {
+        if deviation <= previous.0 || !cost.is_finite() || cost < previous.1 {
+            return Err(invalid);
+        }
+        previous = (deviation, cost);
+        cost_points.push((
+            seconds("soft_time_windows", Some(location), deviation)?,
+            cost,
+        ));
+    }
+    Ok(CostFunction::new(cost_points))
+}
+
+fn transform_soft_window(
+    location: usize,
+    soft_window: &RawSoftWindow,
+) -> Result<Option<SoftWindow>, InputError> {
+    let Some((start, end, earliness, lateness)) = soft_window else {
+        return Ok(None);
+    };
+    if start > end {
+        return Err(InputError::InvalidSoftTimeWindow {
+            location,
+            reason: "ends before it starts",
+        });
+    }
+    Ok(Some(SoftWindow {
+        window: TimeWindow::new(
+            timestamp("soft_time_windows", Some(location), *start)?,
+            timestamp("soft_time_windows", Some(location), *end)?,
+        ),
+        earliness: transform_cost_function(location, earliness)?,
+        lateness: transform_cost_function(location, lateness)?,
+    }))
+}
+
+fn transform_time_windows(
+    location: usize,
+    windows: &RawTimeWindows,
+) -> Result<TimeWindows, InputError> {
+    let windows = windows.as_deref().unwrap_or_default();
+    let mut time_windows = Vec::with_capacity(windows.len());
+    for (window, &(start, end)) in windows.iter().enumerate() {
+        if start > end {
+            return Err(InputError::InvalidTimeWindow {
+                location,
+                window,
+                start,
+                end,
+            });
+        }
+        if window > 0 && start < windows[window - 1].1 {
+            return Err(InputError::UnorderedTimeWindows { location, window });
+        }
+        time_windows.push(TimeWindow::new(
+            timestamp("time_windows", Some(location), start)?,
+            timestamp("time_windows", Some(location), end)?,
+        ));
+    }
+    Ok(TimeWindows::new(time_windows))
 }
 
-pub fn transform(
-    duration_matrix: Option<Vec<Vec<u64>>>,
-    job_durations: Option<Vec<u64>>,
-    time_windows: Option<Vec<Vec<(u64, u64)>>>,
+fn transform_working_days(days: &[bool]) -> Result<Vec<chrono::Weekday>, InputError> {
+    if days.len() > 7 || !days.contains(&true) {
+        return Err(InputError::InvalidWorkingDays { len: days.len() });
+    }
+    Ok(days
+        .iter()
+        .enumerate()
+        .filter(|&(_, &working)| working)
+        // the day index is below 7, so it always is a weekday
+        .map(|(day, _)| chrono::Weekday::try_from(day as u8).unwrap())
+        .collect())
+}
+
+/// Ordered shifts of a single day, `day` naming the day in errors.
+fn transform_shifts(
+    field: &'static str,
+    day: &dyn Fn() -> String,
+    shifts: &[(u64, u64)],
+) -> Result<Vec<Shift>, InputError> {
+    let mut result: Vec<Shift> = Vec::with_capacity(shifts.len());
+    for &(start, end) in shifts {
+        if start >= 24 * 3600 || end > 24 * 3600 {
+            return Err(InputError::InvalidOperationTimes { start, end });
+        }
+        // a shift over midnight can only be the last one of its day
+        if result
+            .last()
+            .is_some_and(|shift| (shift.end as u64) > start)
+        {
+            return Err(InputError::UnorderedShifts { field, day: day() });
+        }
+        result.push(Shift::between(start as u32, end as u32));
+    }
+    Ok(result)
+}
+
+fn transform_weekly_operation_times(
+    weekly: &[Vec<(u64, u64)>],
+) -> Result<[Vec<Shift>; 7], InputError> {
+    let invalid = InputError::InvalidWeeklyOperationTimes { len: weekly.len() };
+    if weekly.len() != 7 || weekly.iter().all(|shifts| shifts.is_empty()) {
+        return Err(invalid);
+    }
+    let mut result: [Vec<Shift>; 7] = Default::default();
+    for (day, shifts) in weekly.iter().enumerate() {
+        result[day] = transform_shifts("weekly_operation_times", &|| day.to_string(), shifts)?;
+    }
+    Ok(result)
+}
+
+/// Shifts on specific dates, given as "YYYY-MM-DD".
+fn transform_exceptions(
+    exceptions: &BTreeMap<String, Vec<(u64, u64)>>,
+) -> Result<BTreeMap<chrono::NaiveDate, Vec<Shift>>, InputError> {
+    exceptions
+        .iter()
+        .map(|(date, shifts)| {
+            let field = "operation_time_exceptions";
+            let parsed = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+                InputError::InvalidOption {
+                    field,
+                    value: date.clone(),
+                }
+            })?;
+            Ok((parsed, transform_shifts(field, &|| date.clone(), shifts)?))
+        })
+        .collect()
+}
+
+/// The calendar of operation times, either the same hours on every working day
+/// or different shifts per weekday, with exceptions on specific dates.
+/// Without any restriction, there is no calendar at all.
+fn transform_operation_times(
     operation_times: Option<(u64, u64)>,
     working_days: Option<Vec<bool>>,
-    travel_duration_until_break: Option<u64>,
-    break_duration: Option<u64>,
-) -> Option<TimeInput> {
-    let duration_matrix = match duration_matrix {
-        Some(matrix) => Some(
+    weekly_operation_times: Option<Vec<Vec<(u64, u64)>>>,
+    operation_time_exceptions: Option<BTreeMap<String, Vec<(u64, u64)>>>,
+) -> Result<Option<OperationTimes>, InputError> {
+    let exceptions = operation_time_exceptions
+        .map(|exceptions| transform_exceptions(&exceptions))
+        .transpose()?;
+    let working_days = working_days
+        .map(|days| transform_working_days(&days))
+        .transpose()?;
+    let weekly = match (operation_times, weekly_operation_times) {
+        (Some(_), Some(_)) => {
+            return Err(InputError::ConflictingOptions {
+                field: "weekly_operation_times",
+                other: "operation_times",
+            })
+        }
+        (None, Some(_)) if working_days.is_some() => {
+            return Err(InputError::ConflictingOptions {
+                field: "weekly_operation_times",
+                other: "working_days",
+            })
+        }
+        (None, Some(weekly)) => transform_weekly_operation_times(&weekly)?,
+        (Some((start, end)), None) if start >= 24 * 3600 || end > 24 * 3600 => {
+            return Err(InputError::InvalidOperationTimes { start, end });
+        }
+        // if they are 24 hours every day, we can ignore operating times
+        (Some((start, end)), None)
+            if (end == start + 24 * 3600 || start == end)
+                && working_days.is_none()
+                && exceptions.is_none() =>
+        {
+            return Ok(None)
+        }
+        (None, None) if exceptions.is_none() => return Ok(None),
+        (daily, None) => {
+            let shift = match daily {
+                Some((start, end)) => Shift::between(start as u32, end as u32),
+                None => Shift::new(0, 24 * 3600),
+            };
+            let mut weekly: [Vec<Shift>; 7] = Default::default();
+            for (day, shifts) in weekly.iter_mut().enumerate() {
+                let working = working_days.as_ref().is_none_or(|working_days| {
+                    working_days
+                        .iter()
+                        .any(|weekday| weekday.num_days_from_monday() as usize == day)
+                });
+                if working {
+                    *shifts = vec![shift];
+                }
+            }
+            weekly
+        }
+    };
+    let exceptions = exceptions.unwrap_or_default();
+    validate_overnight_shifts(&weekly, &exceptions)?;
+    Ok(Some(
+        OperationTimes::weekly(weekly).with_exceptions(exceptions),
+    ))
+}
+
+/// A shift over midnight has to end before the first shift of the next day starts,
+/// which is the day named in the error.
+fn validate_overnight_shifts(
+    weekly: &[Vec<Shift>; 7],
+    exceptions: &BTreeMap<chrono::NaiveDate, Vec<Shift>>,
+) -> Result<(), InputError> {
+    let overlaps = |shifts: &[Shift], next: &[Shift]| match (shifts.last(), next.first()) {
+        (Some(last), Some(first)) => last.end > 24 * 3600 + first.start,
+        _ => false,
+    };
+    for day in 0..7 {
+        let next = (day + 1) % 7;
+        if overlaps(&weekly[day], &weekly[next]) {
+            return Err(InputError::UnorderedShifts {
+                field: "weekly_operation_times",
+                day: next.to_string(),
+            });
+        }
+    }
+    let shifts_on = |date: chrono::NaiveDate| {
+        exceptions
+            .get(&date)
+            .unwrap_or(&weekly[date.weekday().num_days_from_monday() as usize])
+    };
+    // an exception may meet the shifts of the days around it
+    for &date in exceptions.keys() {
+        for (before, after) in [(date.pred_opt(), Some(date)), (Some(date), date.succ_opt())] {
+            if let (Some(before), Some(after)) = (before, after) {
+                if overlaps(shifts_on(before), shifts_on(after)) {
+                    return Err(InputError::UnorderedShifts {
+                        field: "operation_time_exceptions",
+                        day: after.format("%Y-%m-%d").to_string(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validates the options of the time model, None if they do not make up a schedule.
+pub fn transform(raw: RawTimeInput) -> Result<Option<TimeInput>, InputError> {
+    let RawTimeInput {
+        duration_matrix,
+        job_durations,
+        time_windows,
+        operation_times,
+        working_days,
+        travel_duration_until_break,
+        break_duration,
+        time_zone,
+        weekly_operation_times,
+        operation_time_exceptions,
+        horizon_end,
+        max_route_duration,
+        soft_limits,
+        start_time,
+        earliest_departure,
+        soft_time_windows,
+        split_policies,
+        min_split_durations,
+        precedences,
+    } = raw;
+    // whether the options that only apply to a schedule are given, before they are transformed
+    let options_given = [
+        ("duration_matrix", duration_matrix.is_some()),
+        ("job_durations", job_durations.is_some()),
+        ("time_windows", time_windows.is_some()),
+        ("operation_times", operation_times.is_some()),
+        ("working_days", working_days.is_some()),
+        ("weekly_operation_times", weekly_operation_times.is_some()),
+        (
+            "operation_time_exceptions",
+            operation_time_exceptions.is_some(),
+        ),
+        ("time_zone", time_zone.is_some()),
+        (
+            "travel_duration_until_break",
+            travel_duration_until_break.is_some(),
+        ),
+        ("break_duration", break_duration.is_some()),
+        ("soft_limits", soft_limits),
+        ("split_policies", split_policies.is_some()),
+        ("min_split_durations", min_split_durations.is_some()),
+    ];
+    let duration_matrix = duration_matrix
+        .map(|matrix| {
             matrix
                 .iter()
-                .map(|row| {
+                .enumerate()
+                .map(|(location, row)| {
                     row.iter()
-                        .map(|&x| chrono::Duration::seconds(x as i64))
-                        .collect::<Vec<chrono::Duration>>()
+                        .map(|&x| seconds("duration_matrix", Some(location), x))
+                        .collect::<Result<Vec<chrono::Duration>, InputError>>()
                 })
-                .collect::<Vec<Vec<chrono::Duration>>>(),
-        ),
-        None => None,
-    };
-    let job_durations = match job_durations {
-        Some(durations) => Some(
+                .collect::<Result<Vec<Vec<chrono::Duration>>, InputError>>()
+        })
+        .transpose()?;
+    let job_durations = job_durations
+        .map(|durations| {
             durations
                 .iter()
-                .map(|&x| chrono::Duration::seconds(x as i64))
-                .collect::<Vec<chrono::Duration>>(),
-        ),
-        None => None,
-    };
-    let time_windows = match time_windows {
-        Some(windows) => Some(
+                .enumerate()
+                .map(|(location, &x)| seconds("job_durations", Some(location), x))
+                .collect::<Result<Vec<chrono::Duration>, InputError>>()
+        })
+        .transpose()?;
+    let time_windows = time_windows
+        .map(|windows| {
             windows
                 .iter()
-                .map(|window| {
-                    TimeWindows::new(
-                        window
-                            .iter()
-                            .map(|&(start, end)| {
-                                TimeWindow::new(
-                                    chrono::DateTime::from_timestamp(start as i64, 0).unwrap(),
-                                    chrono::DateTime::from_timestamp(end as i64, 0).unwrap(),
-                                )
-                            })
-                            .collect::<Vec<TimeWindow>>(),
-                    )
+                .enumerate()
+                .map(|(location, window)| transform_time_windows(location, window))
+                .collect::<Result<Vec<TimeWindows>, InputError>>()
+        })
+        .transpose()?;
+    let soft_windows = soft_time_windows
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(location, soft_window)| transform_soft_window(location, soft_window))
+        .collect::<Result<Vec<Option<SoftWindow>>, InputError>>()?;
+    let split_policies = transform_split_policies(split_policies, min_split_durations)?;
+    let precedences = precedences
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(index, precedence)| transform_precedence(index, precedence))
+        .collect::<Result<Vec<Precedence>, InputError>>()?;
+    if let Some(index) = closing_precedence(&precedences) {
+        return Err(InputError::InvalidPrecedence {
+            precedence: index,
+            reason: "closes a cycle of precedences",
+        });
+    }
+    let time_zone = time_zone
+        .map(|name| {
+            name.parse::<chrono_tz::Tz>()
+                .map_err(|_| InputError::InvalidOption {
+                    field: "time_zone",
+                    value: name,
                 })
-                .collect::<Vec<TimeWindows>>(),
-        ),
-        None => None,
+        })
+        .transpose()?
+        .unwrap_or(chrono_tz::Tz::UTC);
+    let operation_times = transform_operation_times(
+        operation_times,
+        working_days,
+        weekly_operation_times,
+        operation_time_exceptions,
+    )?
+    .map(|operation_times| operation_times.in_time_zone(time_zone));
+    let travel_duration_until_break = travel_duration_until_break
+        .map(|x| seconds("travel_duration_until_break", None, x))
+        .transpose()?;
+    let break_duration = break_duration
+        .map(|x| seconds("break_duration", None, x))
+        .transpose()?;
+    let limits = Limits {
+        horizon_end: horizon_end
+            .map(|x| timestamp("horizon_end", None, x))
+            .transpose()?,
+        max_duration: max_route_duration
+            .map(|x| seconds("max_route_duration", None, x))
+            .transpose()?,
+        soft: soft_limits,
     };
-    let operation_times = match operation_times {
-        Some((start, end)) => {
-            // if they are 24 hours, we can ignore operating times
-            if end - start == 24 * 3600 || start == end {
-                None
-            } else {
-                Some(OperationTimes::new(
-                    chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap()
-                        + chrono::Duration::seconds(start as i64),
-                    chrono::NaiveTime::from_hms_opt(0, 0, 0).unwrap()
-                        + chrono::Duration::seconds(end as i64),
-                    match working_days {
-                        None => None,
-                        Some(days) => Some(
-                            days.iter()
-                                .enumerate()
-                                .filter_map(|(i, &x)| if x { Some(i) } else { None })
-                                .map(|x| match x {
-                                    0 => chrono::Weekday::Mon,
-                                    1 => chrono::Weekday::Tue,
-                                    2 => chrono::Weekday::Wed,
-                                    3 => chrono::Weekday::Thu,
-                                    4 => chrono::Weekday::Fri,
-                                    5 => chrono::Weekday::Sat,
-                                    6 => chrono::Weekday::Sun,
-                                    _ => panic!("Invalid day"),
-                                })
-                                .collect::<Vec<chrono::Weekday>>(),
-                        ),
-                    },
-                ))
-            }
-        }
-        None => None,
+    let start = Start {
+        time: start_time
+            .map(|x| timestamp("start_time", None, x))
+            .transpose()?,
+        earliest_departure: earliest_departure
+            .map(|x| {
+                u32::try_from(x)
+                    .ok()
+                    .and_then(|x| chrono::NaiveTime::from_num_seconds_from_midnight_opt(x, 0))
+                    .ok_or(InputError::InvalidOption {
+                        field: "earliest_departure",
+                        value: x.to_string(),
+                    })
+            })
+            .transpose()?,
+        time_zone,
     };
-    // Here we could do even more matches like if duration matrix is None, we
-    // will not calculate any travel time, in the calculation, same for job durations.
-    match (
-        duration_matrix,
-        job_durations,
-        time_windows,
-        operation_times,
-    ) {
-        (Some(duration_matrix), Some(job_durations), Some(time_windows), operation_times) => {
-            Some(TimeInput {
+    match (duration_matrix, job_durations) {
+        (Some(duration_matrix), Some(job_durations)) => {
+            // without a list of time windows, every location can be visited at any time
+            let time_windows = time_windows.unwrap_or_else(|| {
+                job_durations
+                    .iter()
+                    .map(|_| TimeWindows::new(vec![]))
+                    .collect()
+            });
+            // the first time window of some location or the start time tells when routes start
+            if start.time.is_none() && time_windows.iter().all(TimeWindows::is_empty) {
+                return Err(InputError::NoTimeWindows);
+            }
+            Ok(Some(TimeInput {
                 duration_matrix,
                 job_durations,
                 time_windows,
                 operation_times,
                 travel_duration_until_break,
                 break_duration,
-            })
+                limits,
+                start,
+                soft_windows,
+                split_policies,
+                precedences,
+            }))
+        }
+        // without a schedule, the options that constrain it would silently have no effect
+        _ => {
+            let orphaned = [
+                ("horizon_end", limits.horizon_end.is_some()),
+                ("max_route_duration", limits.max_duration.is_some()),
+                ("start_time", start.time.is_some()),
+                ("earliest_departure", start.earliest_departure.is_some()),
+                (
+                    "soft_time_windows",
+                    soft_windows.iter().any(Option::is_some),
+                ),
+                ("precedences", !precedences.is_empty()),
+            ]
+            .into_iter()
+            .chain(options_given)
+            .find(|&(_, given)| given);
+            match orphaned {
+                Some((field, _)) => Err(InputError::ConflictingOptions {
+                    field,
+                    other: "a missing duration_matrix or job_durations",
+                }),
+                None => Ok(None),
+            }
         }
-        _ => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use chrono::{TimeZone, Utc};
+    use chrono::{Duration, TimeZone, Utc};
 
     use super::*;
 
     #[test]
     fn test_transform() {
-        let time_input = transform(
-            Some(vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]]),
-            Some(vec![3, 3, 3]),
-            Some(vec![
-                vec![(1, 2), (3, 4)],
-                vec![(5, 6), (7, 8)],
-                vec![(9, 10), (11, 12)],
+        let time_input = transform(RawTimeInput {
+            duration_matrix: Some(vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]]),
+            job_durations: Some(vec![3, 3, 3]),
+            time_windows: Some(vec![
+                Some(vec![(1, 2), (3, 4)]),
+                Some(vec![(5, 6), (7, 8)]),
+                Some(vec![(9, 10), (11, 12)]),
             ]),
-            Some((8, 16)),
-            Some(vec![true, true, true, false, false, true, false]),
-            None,
-            None,
-        );
+            operation_times: Some((8, 16)),
+            working_days: Some(vec![true, true, true, false, false, true, false]),
+            ..RawTimeInput::default()
+        })
+        .unwrap();
         assert!(time_input.is_some());
         let time_input = time_input.unwrap();
         assert_eq!(time_input.travel_time(0, 1), chrono::Duration::seconds(1));
@@ -220,14 +869,7 @@ mod tests {
             chrono::DateTime::from_timestamp(12, 0).unwrap()
         );
         let operation_times = time_input.operation_times.unwrap();
-        assert_eq!(
-            operation_times.start(),
-            chrono::NaiveTime::from_hms_opt(0, 0, 8).unwrap()
-        );
-        assert_eq!(
-            operation_times.end(),
-            chrono::NaiveTime::from_hms_opt(0, 0, 16).unwrap()
-        );
+        assert_eq!(operation_times.daily_shift(), Some(Shift::new(8, 16)));
         // Working days are Mon, Tue, Wed and Sat
         // Mon -> Tue
         assert_eq!(
@@ -264,4 +906,510 @@ mod tests {
             chrono::NaiveDate::from_ymd_opt(2024, 1, 8).unwrap()
         );
     }
+
+    #[test]
+    fn test_departure() {
+        let time = Utc.with_ymd_and_hms(2025, 1, 6, 5, 0, 0).unwrap();
+        assert_eq!(Start::default().departure(time), time);
+        let start = Start {
+            earliest_departure: chrono::NaiveTime::from_hms_opt(7, 0, 0),
+            ..Start::default()
+        };
+        assert_eq!(
+            start.departure(time),
+            Utc.with_ymd_and_hms(2025, 1, 6, 7, 0, 0).unwrap()
+        );
+        // later in the day, the route leaves right away
+        let later = Utc.with_ymd_and_hms(2025, 1, 6, 9, 0, 0).unwrap();
+        assert_eq!(start.departure(later), later);
+        // 07:00 in Berlin is 06:00 UTC in winter
+        let start = Start {
+            time_zone: chrono_tz::Europe::Berlin,
+            ..start
+        };
+        assert_eq!(
+            start.departure(time),
+            Utc.with_ymd_and_hms(2025, 1, 6, 6, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_transform_errors() {
+        let transform_windows = |time_windows: Vec<Vec<(u64, u64)>>| {
+            transform(RawTimeInput {
+                duration_matrix: Some(vec![vec![0, 1], vec![1, 0]]),
+                job_durations: Some(vec![3, 3]),
+                time_windows: Some(time_windows.into_iter().map(Some).collect()),
+                ..RawTimeInput::default()
+            })
+            .err()
+        };
+        assert_eq!(
+            transform_windows(vec![vec![(1, 2)], vec![(6, 5)]]),
+            Some(InputError::InvalidTimeWindow {
+                location: 1,
+                window: 0,
+                start: 6,
+                end: 5
+            })
+        );
+        assert_eq!(
+            transform_windows(vec![vec![(1, 4), (3, 5)], vec![(1, 2)]]),
+            Some(InputError::UnorderedTimeWindows {
+                location: 0,
+                window: 1
+            })
+        );
+        // a location without time windows can be visited at any time,
+        // but some location has to tell when routes start
+        assert_eq!(transform_windows(vec![vec![(1, 2)], vec![]]), None);
+        assert_eq!(
+            transform_windows(vec![vec![], vec![]]),
+            Some(InputError::NoTimeWindows)
+        );
+        assert_eq!(
+            transform_windows(vec![vec![(1, u64::MAX)], vec![(1, 2)]]),
+            Some(InputError::TimestampOutOfRange {
+                field: "time_windows",
+                location: Some(0),
+                value: u64::MAX
+            })
+        );
+        assert_eq!(
+            transform(RawTimeInput {
+                operation_times: Some((86400, 8)),
+                ..RawTimeInput::default()
+            })
+            .err(),
+            Some(InputError::InvalidOperationTimes {
+                start: 86400,
+                end: 8
+            })
+        );
+        assert_eq!(
+            transform(RawTimeInput {
+                operation_times: Some((8, 16)),
+                working_days: Some(vec![false; 7]),
+                ..RawTimeInput::default()
+            })
+            .err(),
+            Some(InputError::InvalidWorkingDays { len: 7 })
+        );
+        assert_eq!(
+            transform(RawTimeInput {
+                operation_times: Some((8, 16)),
+                working_days: Some(vec![true; 8]),
+                ..RawTimeInput::default()
+            })
+            .err(),
+            Some(InputError::InvalidWorkingDays { len: 8 })
+        );
+        assert_eq!(
+            transform(RawTimeInput {
+                operation_times: Some((8, 16)),
+                time_zone: Some("Europe/Gotham".to_string()),
+                ..RawTimeInput::default()
+            })
+            .err(),
+            Some(InputError::InvalidOption {
+                field: "time_zone",
+                value: "Europe/Gotham".to_string()
+            })
+        );
+        assert_eq!(
+            transform(RawTimeInput {
+                horizon_end: Some(u64::MAX),
+                ..RawTimeInput::default()
+            })
+            .err(),
+            Some(InputError::TimestampOutOfRange {
+                field: "horizon_end",
+                location: None,
+                value: u64::MAX
+            })
+        );
+        assert_eq!(
+            transform(RawTimeInput {
+                earliest_departure: Some(86400),
+                ..RawTimeInput::default()
+            })
+            .err(),
+            Some(InputError::InvalidOption {
+                field: "earliest_departure",
+                value: "86400".to_string()
+            })
+        );
+        let transform_soft_window = |soft_window| {
+            transform(RawTimeInput {
+                duration_matrix: Some(vec![vec![0]]),
+                job_durations: Some(vec![3]),
+                time_windows: Some(vec![None]),
+                start_time: Some(1),
+                soft_time_windows: Some(vec![Some(soft_window)]),
+                ..RawTimeInput::default()
+            })
+        };
+        assert_eq!(
+            transform_soft_window((5, 4, vec![], vec![])).err(),
+            Some(InputError::InvalidSoftTimeWindow {
+                location: 0,
+                reason: "ends before it starts"
+            })
+        );
+        assert_eq!(
+            transform_soft_window((4, 5, vec![(60, 2.0), (60, 3.0)], vec![])).err(),
+            Some(InputError::InvalidSoftTimeWindow {
+                location: 0,
+                reason: "has cost points that are not increasing from zero"
+            })
+        );
+        assert_eq!(
+            transform_soft_window((4, 5, vec![], vec![(60, -1.0)])).err(),
+            Some(InputError::InvalidSoftTimeWindow {
+                location: 0,
+                reason: "has cost points that are not increasing from zero"
+            })
+        );
+        let soft_windows = transform_soft_window((4, 5, vec![(60, 2.0)], vec![]))
+            .unwrap()
+            .unwrap()
+            .soft_windows;
+        assert_eq!(
+            soft_windows[0]
+                .as_ref()
+                .unwrap()
+                .earliness
+                .cost(chrono::Duration::minutes(2)),
+            4.0
+        );
+        assert_eq!(
+            transform_split_policies(
+                Some(vec!["forbid".to_string(), "across_days".to_string()]),
+                Some(vec![3600, 7200]),
+            ),
+            Ok(vec![
+                SplitPolicy::Forbid,
+                SplitPolicy::AcrossDays {
+                    min_part: chrono::Duration::hours(2)
+                }
+            ])
+        );
+        assert_eq!(
+            transform_split_policies(None, Some(vec![3600])),
+            Ok(vec![SplitPolicy::Allow {
+                min_part: chrono::Duration::hours(1)
+            }])
+        );
+        assert_eq!(
+            transform_split_policies(Some(vec!["never".to_string()]), None),
+            Err(InputError::InvalidOption {
+                field: "split_policies",
+                value: "never".to_string()
+            })
+        );
+        // shifts over midnight are fine, but only as the last one of a day
+        let overnight = transform_operation_times(Some((79200, 21600)), None, None, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(overnight.daily_shift(), Some(Shift::new(79200, 108000)));
+        let mut shifts = vec![vec![]; 7];
+        shifts[6] = vec![(79200, 21600), (23 * 3600, 86400)];
+        assert_eq!(
+            transform_operation_times(None, None, Some(shifts), None).err(),
+            Some(InputError::UnorderedShifts {
+                field: "weekly_operation_times",
+                day: "6".to_string()
+            })
+        );
+        // nor may they run into the first shift of the next day, also from Sunday into Monday
+        let weekly_error = |weekly: Vec<Vec<(u64, u64)>>| {
+            transform_operation_times(None, None, Some(weekly), None).err()
+        };
+        let mut shifts = vec![vec![]; 7];
+        shifts[0] = vec![(79200, 21600)];
+        shifts[1] = vec![(14400, 43200)];
+        assert_eq!(
+            weekly_error(shifts.clone()),
+            Some(InputError::UnorderedShifts {
+                field: "weekly_operation_times",
+                day: "1".to_string()
+            })
+        );
+        shifts[1] = vec![(21600, 43200)];
+        assert_eq!(weekly_error(shifts.clone()), None);
+        shifts[6] = vec![(82800, 82800)];
+        assert_eq!(
+            weekly_error(shifts.clone()),
+            Some(InputError::UnorderedShifts {
+                field: "weekly_operation_times",
+                day: "0".to_string()
+            })
+        );
+        // or into and out of exceptions, 2025-01-06 being a Monday
+        let with_exception = |date: &str, shifts: Vec<(u64, u64)>| {
+            transform_operation_times(
+                Some((79200, 21600)),
+                None,
+                None,
+                Some(BTreeMap::from([(date.to_string(), shifts)])),
+            )
+            .err()
+        };
+        assert_eq!(with_exception("2025-01-06", vec![(21600, 36000)]), None);
+        assert_eq!(
+            with_exception("2025-01-06", vec![(18000, 36000)]),
+            Some(InputError::UnorderedShifts {
+                field: "operation_time_exceptions",
+                day: "2025-01-06".to_string()
+            })
+        );
+        assert_eq!(
+            with_exception("2025-01-06", vec![(82800, 82800)]),
+            Some(InputError::UnorderedShifts {
+                field: "operation_time_exceptions",
+                day: "2025-01-07".to_string()
+            })
+        );
+        // 24 hours from 08:00 on working days
+        let round_the_clock = transform_operation_times(
+            Some((28800, 28800)),
+            Some(vec![true, false, true]),
+            None,
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            round_the_clock.daily_shift(),
+            Some(Shift::new(28800, 115200))
+        );
+        let weekly = |weekly: Vec<Vec<(u64, u64)>>| {
+            transform_operation_times(None, None, Some(weekly), None).err()
+        };
+        assert_eq!(
+            weekly(vec![vec![(8, 16)]; 6]),
+            Some(InputError::InvalidWeeklyOperationTimes { len: 6 })
+        );
+        assert_eq!(
+            weekly(vec![vec![]; 7]),
+            Some(InputError::InvalidWeeklyOperationTimes { len: 7 })
+        );
+        let mut shifts = vec![vec![]; 7];
+        shifts[2] = vec![(8, 12), (11, 16)];
+        assert_eq!(
+            weekly(shifts),
+            Some(InputError::UnorderedShifts {
+                field: "weekly_operation_times",
+                day: "2".to_string()
+            })
+        );
+        assert_eq!(
+            transform_operation_times(Some((8, 16)), None, Some(vec![vec![(8, 16)]; 7]), None)
+                .err(),
+            Some(InputError::ConflictingOptions {
+                field: "weekly_operation_times",
+                other: "operation_times"
+            })
+        );
+        let exceptions = |date: &str, shifts: Vec<(u64, u64)>| {
+            transform_operation_times(
+                Some((8, 16)),
+                None,
+                None,
+                Some(BTreeMap::from([(date.to_string(), shifts)])),
+            )
+            .err()
+        };
+        assert_eq!(exceptions("2025-12-25", vec![]), None);
+        assert_eq!(
+            exceptions("25.12.2025", vec![]),
+            Some(InputError::InvalidOption {
+                field: "operation_time_exceptions",
+                value: "25.12.2025".to_string()
+            })
+        );
+        assert_eq!(
+            exceptions("2025-12-24", vec![(8, 86401)]),
+            Some(InputError::InvalidOperationTimes {
+                start: 8,
+                end: 86401
+            })
+        );
+        // without a list of time windows, every location can be visited any time after the start
+        let without_time_windows = |start_time| {
+            transform(RawTimeInput {
+                duration_matrix: Some(vec![vec![0, 1], vec![1, 0]]),
+                job_durations: Some(vec![3, 3]),
+                start_time,
+                precedences: Some(vec![(0, 1, None, None)]),
+                ..RawTimeInput::default()
+            })
+        };
+        assert_eq!(
+            without_time_windows(None).err(),
+            Some(InputError::NoTimeWindows)
+        );
+        let time_input = without_time_windows(Some(1735714800)).unwrap().unwrap();
+        assert_eq!(time_input.time_windows.len(), 2);
+        assert!(time_input.time_windows.iter().all(TimeWindows::is_empty));
+        assert_eq!(time_input.precedences.len(), 1);
+        // options of the schedule need the rest of the time model
+        let orphaned = |field, raw: RawTimeInput| {
+            assert_eq!(
+                transform(raw).err(),
+                Some(InputError::ConflictingOptions {
+                    field,
+                    other: "a missing duration_matrix or job_durations"
+                })
+            );
+        };
+        orphaned(
+            "duration_matrix",
+            RawTimeInput {
+                duration_matrix: Some(vec![vec![0, 1], vec![1, 0]]),
+                time_windows: Some(vec![None, None]),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "job_durations",
+            RawTimeInput {
+                job_durations: Some(vec![3, 3]),
+                time_windows: Some(vec![None, None]),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "time_windows",
+            RawTimeInput {
+                time_windows: Some(vec![Some(vec![(1, 2)]), None]),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "operation_times",
+            RawTimeInput {
+                operation_times: Some((8, 16)),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "working_days",
+            RawTimeInput {
+                working_days: Some(vec![true; 5]),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "weekly_operation_times",
+            RawTimeInput {
+                weekly_operation_times: Some(vec![vec![(8, 16)]; 7]),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "operation_time_exceptions",
+            RawTimeInput {
+                operation_time_exceptions: Some(BTreeMap::from([(
+                    "2025-12-25".to_string(),
+                    vec![],
+                )])),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "time_zone",
+            RawTimeInput {
+                time_zone: Some("Europe/Berlin".to_string()),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "travel_duration_until_break",
+            RawTimeInput {
+                travel_duration_until_break: Some(3600),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "break_duration",
+            RawTimeInput {
+                break_duration: Some(600),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "soft_limits",
+            RawTimeInput {
+                soft_limits: true,
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "split_policies",
+            RawTimeInput {
+                split_policies: Some(vec!["forbid".to_string(); 2]),
+                ..RawTimeInput::default()
+            },
+        );
+        orphaned(
+            "min_split_durations",
+            RawTimeInput {
+                min_split_durations: Some(vec![0, 0]),
+                ..RawTimeInput::default()
+            },
+        );
+        assert_eq!(
+            transform_precedence(2, &(1, 3, Some(60), None)),
+            Ok(Precedence {
+                before: 1,
+                after: 3,
+                min_lag: Duration::seconds(60),
+                max_lag: None
+            })
+        );
+        assert_eq!(
+            transform_precedence(2, &(1, 1, None, None)),
+            Err(InputError::InvalidPrecedence {
+                precedence: 2,
+                reason: "relates a job to itself"
+            })
+        );
+        assert_eq!(
+            transform_precedence(2, &(1, 3, Some(60), Some(30))),
+            Err(InputError::InvalidPrecedence {
+                precedence: 2,
+                reason: "has a maximum lag below its minimum lag"
+            })
+        );
+        let closing = |precedences: &[(usize, usize)]| {
+            closing_precedence(
+                &precedences
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &(before, after))| {
+                        transform_precedence(index, &(before, after, None, None)).unwrap()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(closing(&[(0, 1), (1, 2), (0, 2), (3, 1)]), None);
+        assert_eq!(closing(&[(0, 1), (1, 0)]), Some(1));
+        assert_eq!(closing(&[(0, 1), (2, 3), (1, 2), (3, 0), (1, 0)]), Some(3));
+        assert_eq!(
+            transform(RawTimeInput {
+                duration_matrix: Some(vec![vec![0, 1], vec![1, 0]]),
+                job_durations: Some(vec![3, 3]),
+                time_windows: Some(vec![None, None]),
+                start_time: Some(1735714800),
+                precedences: Some(vec![(0, 1, None, None), (1, 0, Some(60), None)]),
+                ..RawTimeInput::default()
+            })
+            .err(),
+            Some(InputError::InvalidPrecedence {
+                precedence: 1,
+                reason: "closes a cycle of precedences"
+            })
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use super::time_windows::{TimeWindow, TimeWindows};
+use super::{soft_windows::JobPenalty, time_windows::TimeWindow};
 
 /// Time report module for outputs and evaluation of the time schedule.
 
@@ -11,16 +11,32 @@ pub struct TimeOutput<S: CompletionState> {
     pub working_time: chrono::Duration,
     pub waiting_time: chrono::Duration,
     pub traveling_time: chrono::Duration,
+    pub break_time: chrono::Duration,
     pub job_splits: u32,
+    /// Number of jobs that had to be split against their split policy.
+    pub split_violations: u32,
+    /// How far the route exceeds the planning horizon or its maximum duration.
+    pub overtime: chrono::Duration,
+    /// Like `overtime`, if the limits are soft.
+    pub excess: chrono::Duration,
+    /// The total cost of missing the preferred time windows.
+    pub soft_window_cost: f64,
     pub schedule: Vec<Event>,
+    /// The jobs that miss their preferred time windows, built along with the schedule.
+    pub penalties: Vec<JobPenalty>,
+    /// The visits of the route, built along with the schedule.
+    pub stops: Vec<Stop>,
+    /// When each job is served, only tracked for the jobs that precedences relate.
+    pub services: Vec<Service>,
     phantom: std::marker::PhantomData<S>,
 }
 
+#[derive(Debug, Clone)]
 pub enum Incomplete {}
 #[derive(Debug, Clone)]
 pub enum Complete {}
 
-trait CompletionState {}
+pub trait CompletionState {}
 impl CompletionState for Incomplete {}
 impl CompletionState for Complete {}
 
@@ -34,8 +50,16 @@ impl TimeOutput<Incomplete> {
             working_time: chrono::Duration::zero(),
             waiting_time: chrono::Duration::zero(),
             traveling_time: chrono::Duration::zero(),
+            break_time: chrono::Duration::zero(),
             job_splits: 0,
+            split_violations: 0,
+            overtime: chrono::Duration::zero(),
+            excess: chrono::Duration::zero(),
+            soft_window_cost: 0.0,
             schedule: vec![],
+            penalties: vec![],
+            stops: vec![],
+            services: vec![],
             phantom: std::marker::PhantomData,
         }
     }
@@ -57,6 +81,15 @@ impl TimeOutput<Incomplete> {
             self.schedule.push(Event::Travel(time_window));
         }
     }
+    pub fn add_break(&mut self, time_window: TimeWindow, build_schedule: bool) {
+        let duration = time_window.duration();
+        self.break_time += duration;
+        self.end_time += duration;
+        self.duration += duration;
+        if build_schedule {
+            self.schedule.push(Event::Break(time_window));
+        }
+    }
     pub fn add_working(&mut self, location: usize, time_window: TimeWindow, build_schedule: bool) {
         let duration = time_window.duration();
         self.working_time += duration;
@@ -69,9 +102,37 @@ impl TimeOutput<Incomplete> {
     pub fn add_split(&mut self) {
         self.job_splits += 1;
     }
+    pub fn add_split_violation(&mut self) {
+        self.split_violations += 1;
+    }
     pub fn add_lateness(&mut self, lateness: chrono::Duration) {
         self.lateness += lateness;
     }
+    pub fn add_overtime(&mut self, overtime: chrono::Duration) {
+        self.overtime += overtime;
+    }
+    pub fn add_excess(&mut self, excess: chrono::Duration) {
+        self.excess += excess;
+    }
+    pub fn add_penalty(&mut self, penalty: JobPenalty, build_schedule: bool) {
+        self.soft_window_cost += penalty.cost;
+        if build_schedule && penalty.cost > 0.0 {
+            self.penalties.push(penalty);
+        }
+    }
+    /// Stops are only added while building the schedule.
+    pub fn add_stop(&mut self, stop: Stop) {
+        self.stops.push(stop);
+    }
+    pub fn add_service(&mut self, service: Service) {
+        self.services.push(service);
+    }
+    /// The vehicle leaves the last stop at the given time.
+    pub fn depart(&mut self, departure: chrono::DateTime<chrono::Utc>) {
+        if let Some(stop) = self.stops.last_mut() {
+            stop.departure = departure;
+        }
+    }
     pub fn complete(self) -> TimeOutput<Complete> {
         TimeOutput {
             start_time: self.start_time,
@@ -81,18 +142,102 @@ impl TimeOutput<Incomplete> {
             working_time: self.working_time,
             waiting_time: self.waiting_time,
             traveling_time: self.traveling_time,
+            break_time: self.break_time,
             job_splits: self.job_splits,
+            split_violations: self.split_violations,
+            overtime: self.overtime,
+            excess: self.excess,
+            soft_window_cost: self.soft_window_cost,
             schedule: self.schedule,
+            penalties: self.penalties,
+            stops: self.stops,
+            services: self.services,
             phantom: std::marker::PhantomData,
         }
     }
 }
 
+impl TimeOutput<Complete> {
+    /// Whether the schedule keeps its hard limits and the split policies of its jobs.
+    pub fn is_feasible(&self) -> bool {
+        self.overtime == chrono::Duration::zero() && self.split_violations == 0
+    }
+
+    /// Combines the reports of several routes into a report over all of them.
+    /// Times and penalties are summed up, the schedules are appended one after the other.
+    pub fn combine(reports: &[&TimeOutput<Complete>]) -> Option<TimeOutput<Complete>> {
+        let mut combined = TimeOutput::combine_totals(reports)?;
+        for report in reports {
+            combined.schedule.extend(report.schedule.iter().cloned());
+            combined.penalties.extend(report.penalties.iter().cloned());
+            combined.stops.extend(report.stops.iter().cloned());
+        }
+        Some(combined)
+    }
+
+    /// Like `combine`, but only with the totals and the services the objective needs,
+    /// without the schedules, penalties and stops, so moves can be compared cheaply while searching.
+    pub fn combine_totals(reports: &[&TimeOutput<Complete>]) -> Option<TimeOutput<Complete>> {
+        let (first, rest) = reports.split_first()?;
+        let mut combined = TimeOutput {
+            schedule: vec![],
+            penalties: vec![],
+            stops: vec![],
+            services: first.services.clone(),
+            ..**first
+        };
+        for report in rest {
+            combined.start_time = combined.start_time.min(report.start_time);
+            combined.end_time = combined.end_time.max(report.end_time);
+            combined.duration += report.duration;
+            combined.lateness += report.lateness;
+            combined.working_time += report.working_time;
+            combined.waiting_time += report.waiting_time;
+            combined.traveling_time += report.traveling_time;
+            combined.break_time += report.break_time;
+            combined.job_splits += report.job_splits;
+            combined.split_violations += report.split_violations;
+            combined.overtime += report.overtime;
+            combined.excess += report.excess;
+            combined.soft_window_cost += report.soft_window_cost;
+            combined.services.extend(report.services.iter().cloned());
+        }
+        Some(combined)
+    }
+}
+
+/// The visit of a location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stop {
+    pub location: usize,
+    pub arrival: chrono::DateTime<chrono::Utc>,
+    pub service_start: chrono::DateTime<chrono::Utc>,
+    pub service_end: chrono::DateTime<chrono::Utc>,
+    /// When the vehicle leaves for the next location, the end of the service if there is none.
+    pub departure: chrono::DateTime<chrono::Utc>,
+    /// Waiting between the arrival and the start of the service.
+    pub waiting_time: chrono::Duration,
+    pub lateness: chrono::Duration,
+    /// Index of the time window of the location the service starts in, if any.
+    pub time_window: Option<usize>,
+    /// Whether the service had to be split.
+    pub split: bool,
+}
+
+/// When the job at a location is served, from the start of its first part to the end of its last part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Service {
+    pub location: usize,
+    pub start: chrono::DateTime<chrono::Utc>,
+    pub end: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
     Work(TimeWindow, usize),
     Travel(TimeWindow),
     Wait(TimeWindow),
+    Break(TimeWindow),
 }
 
 // #[cfg(test)]
@@ -17,7 +17,7 @@ impl TimeWindow {
         TimeWindow { start, end }
     }
 
-    fn contains(&self, time: DateTime<Utc>) -> bool {
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
         self.start <= time && time <= self.end
     }
 
@@ -36,6 +36,7 @@ impl TimeWindow {
 
 /// A collection of time windows.
 /// Time windows are stored in chronological order and do not overlap.
+/// Without any time window, a location can be visited at any time.
 pub struct TimeWindows {
     pub windows: Vec<TimeWindow>,
 }
@@ -43,7 +44,7 @@ pub struct TimeWindows {
 impl TimeWindows {
     /// Creates a new empty collection of time windows.
     pub fn new(windows: Vec<TimeWindow>) -> TimeWindows {
-        TimeWindows { windows: windows }
+        TimeWindows { windows }
     }
 
     /// Adds a new time window to the collection.
@@ -76,9 +77,9 @@ impl TimeWindows {
         //    or if false, fits as much as possible
         // return None if no such time window exists
 
-        // if no windows, return None
-        if self.windows.is_empty() {
-            return None;
+        // if no windows, the job can be done right away
+        if self.is_empty() {
+            return Some(TimeWindow::new(current_time, current_time + job_duration));
         }
         // binary search: find the index of the first window that starts after current_time or contains it
         // comparing with the end of the windows,
@@ -103,12 +104,11 @@ impl TimeWindows {
                 // we can use the first time window, just check whether window's duration is larger or job's duration
                 let start = max(self.windows[index].start, current_time);
                 let end = start + min(self.windows[index].duration(), job_duration);
-                return Some(TimeWindow::new(start, end));
+                Some(TimeWindow::new(start, end))
             }
             true => {
                 // Here, we have to iterate over the remaining time windows until we find on that fits the job duration
-                return self
-                    .windows
+                self.windows
                     .iter()
                     .skip(index)
                     .find(|window| window.duration() >= job_duration) // TODO is there a better way to do this? Maybe, there is a leetcode problem for this
@@ -117,13 +117,13 @@ impl TimeWindows {
                             max(window.start, current_time),
                             max(window.start, current_time) + job_duration,
                         )
-                    });
+                    })
             }
         }
     }
 
     pub fn lateness(&self, time: DateTime<Utc>) -> chrono::Duration {
-        if self.windows.is_empty() {
+        if self.is_empty() {
             return chrono::Duration::zero();
         }
         self.windows.last().unwrap().lateness(time)
@@ -251,4 +251,18 @@ mod tests {
         );
         assert_eq!(result, None);
     }
+
+    #[test]
+    fn test_no_time_windows() {
+        let time_windows = TimeWindows::new(vec![]);
+        let time = Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            time_windows.find_next_fitting_time(time, chrono::Duration::hours(3), true),
+            Some(TimeWindow::new(time, time + chrono::Duration::hours(3)))
+        );
+        assert_eq!(
+            time_windows.lateness(time + chrono::Duration::weeks(1)),
+            chrono::Duration::zero()
+        );
+    }
 }
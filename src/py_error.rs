@@ -0,0 +1,104 @@
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*};
+
+use crate::{input::error, schema};
+
+create_exception!(
+    traveling_rustling,
+    InputError,
+    PyValueError,
+    "The input of solve is invalid."
+);
+create_exception!(
+    traveling_rustling,
+    ShapeError,
+    InputError,
+    "A matrix or a per-location input does not match the number of locations."
+);
+create_exception!(
+    traveling_rustling,
+    TimeWindowError,
+    InputError,
+    "The time windows of a location are invalid."
+);
+create_exception!(
+    traveling_rustling,
+    OperationTimesError,
+    InputError,
+    "The operation times or working days are invalid."
+);
+create_exception!(
+    traveling_rustling,
+    OutOfRangeError,
+    InputError,
+    "A timestamp or duration is too large to be represented."
+);
+create_exception!(
+    traveling_rustling,
+    LocationError,
+    InputError,
+    "A route or vehicle refers to locations in an invalid way."
+);
+create_exception!(
+    traveling_rustling,
+    OptionError,
+    InputError,
+    "An option of the solver has an unsupported value."
+);
+
+create_exception!(
+    traveling_rustling,
+    SchemaError,
+    PyValueError,
+    "A JSON document does not match the schema of this version."
+);
+
+impl From<error::InputError> for PyErr {
+    fn from(err: error::InputError) -> PyErr {
+        let message = err.to_string();
+        match err {
+            error::InputError::EmptyMatrix { .. }
+            | error::InputError::NonSquareMatrix { .. }
+            | error::InputError::LengthMismatch { .. }
+            | error::InputError::DimensionMismatch { .. } => ShapeError::new_err(message),
+            error::InputError::NoTimeWindows
+            | error::InputError::InvalidTimeWindow { .. }
+            | error::InputError::UnorderedTimeWindows { .. }
+            | error::InputError::InvalidSoftTimeWindow { .. } => TimeWindowError::new_err(message),
+            error::InputError::InvalidOperationTimes { .. }
+            | error::InputError::InvalidWorkingDays { .. }
+            | error::InputError::InvalidWeeklyOperationTimes { .. }
+            | error::InputError::UnorderedShifts { .. } => OperationTimesError::new_err(message),
+            error::InputError::TimestampOutOfRange { .. }
+            | error::InputError::DurationOutOfRange { .. } => OutOfRangeError::new_err(message),
+            error::InputError::LocationOutOfRange { .. }
+            | error::InputError::MissingLocation { .. }
+            | error::InputError::ConflictingOptions { .. }
+            | error::InputError::DepotLocation { .. }
+            | error::InputError::DuplicateLocation { .. }
+            | error::InputError::UnbalancedPickupDelivery { .. } => LocationError::new_err(message),
+            error::InputError::InvalidPrecedence { .. }
+            | error::InputError::TooManyLocations { .. }
+            | error::InputError::InvalidOption { .. } => OptionError::new_err(message),
+        }
+    }
+}
+
+impl From<schema::SchemaError> for PyErr {
+    fn from(err: schema::SchemaError) -> PyErr {
+        SchemaError::new_err(err.to_string())
+    }
+}
+
+/// Registers the exceptions, so they can be caught from Python.
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("InputError", py.get_type::<InputError>())?;
+    m.add("ShapeError", py.get_type::<ShapeError>())?;
+    m.add("TimeWindowError", py.get_type::<TimeWindowError>())?;
+    m.add("OperationTimesError", py.get_type::<OperationTimesError>())?;
+    m.add("OutOfRangeError", py.get_type::<OutOfRangeError>())?;
+    m.add("LocationError", py.get_type::<LocationError>())?;
+    m.add("OptionError", py.get_type::<OptionError>())?;
+    m.add("SchemaError", py.get_type::<SchemaError>())?;
+    Ok(())
+}
@@ -1,125 +1,481 @@
-use crate::output::Solution;
-use crate::penalties::time::time_output::Event;
-use pyo3::prelude::*;
-use pyo3::{pyclass, pymethods, types::PyDelta};
+use crate::output::Output;
+use crate::schema::{
+    CapacityTotals, DroppedReport, PenaltyReport, PrecedenceReport, RouteReport, ScheduleEvent,
+    SolutionReport, StopReport, TimeTotals,
+};
+use pyo3::{pyclass, pymethods, PyResult};
 
 #[pyclass]
 pub struct PyOutput {
-    pub solution: Solution,
-    pub iterations: u64,
-    pub time_taken: chrono::Duration,
+    pub report: SolutionReport,
 }
 
 impl PyOutput {
-    pub fn new(solution: Solution, iterations: u64, time_taken: chrono::Duration) -> Self {
+    pub fn new(output: Output) -> Self {
         PyOutput {
-            solution,
-            iterations,
-            time_taken,
+            report: SolutionReport::from(&output),
         }
     }
+    fn time(&self) -> Option<&TimeTotals> {
+        self.report.time.as_ref()
+    }
+    fn capacity(&self) -> Option<&CapacityTotals> {
+        self.report.capacity.as_ref()
+    }
 }
 
 #[pymethods]
 impl PyOutput {
     #[getter]
     fn distance(&self) -> u64 {
-        self.solution.distance
+        self.report.distance
     }
+    /// The route of the first vehicle, see `routes` for all vehicles.
+    /// Empty if there are no routes, e.g. in a report read by `from_json`.
     #[getter]
     fn route(&self) -> Vec<usize> {
-        self.solution.route.sequence.clone()
+        self.report
+            .routes
+            .first()
+            .map_or_else(Vec::new, |report| report.route.clone())
+    }
+    #[getter]
+    fn routes(&self) -> Vec<PyRoute> {
+        self.report
+            .routes
+            .iter()
+            .map(|report| PyRoute {
+                report: report.clone(),
+            })
+            .collect()
+    }
+    /// The stops of all routes, one route after the other.
+    #[getter]
+    fn stops(&self) -> Vec<PyStop> {
+        self.report.routes.iter().flat_map(py_stops).collect()
+    }
+    /// Whether the routes keep the planning horizon, the maximum route duration,
+    /// the split policies of their jobs, the precedences, the capacity and the pickup and delivery pairs.
+    #[getter]
+    fn feasible(&self) -> bool {
+        self.report.feasible
+    }
+    /// The optional jobs that are not part of any route, with the reason why.
+    #[getter]
+    fn dropped(&self) -> Vec<PyDroppedJob> {
+        self.report
+            .dropped
+            .iter()
+            .map(|report| PyDroppedJob {
+                report: report.clone(),
+            })
+            .collect()
+    }
+    /// The precedences between jobs that the routes violate.
+    #[getter]
+    fn precedence_violations(&self) -> Vec<PyPrecedenceViolation> {
+        self.report
+            .precedence_violations
+            .iter()
+            .map(|report| PyPrecedenceViolation {
+                report: report.clone(),
+            })
+            .collect()
     }
     #[getter]
     fn lateness(&self) -> Option<u64> {
-        match &self.solution.time_report {
-            Some(time_report) => Some(time_report.lateness.num_seconds() as u64),
-            None => None,
-        }
+        self.time().map(|time| time.lateness)
+    }
+    #[getter]
+    fn overtime(&self) -> Option<u64> {
+        self.time().map(|time| time.overtime)
+    }
+    #[getter]
+    fn excess(&self) -> Option<u64> {
+        self.time().map(|time| time.excess)
+    }
+    #[getter]
+    fn split_violations(&self) -> Option<u32> {
+        self.time().map(|time| time.split_violations)
+    }
+    #[getter]
+    fn soft_window_cost(&self) -> Option<f64> {
+        self.time().map(|time| time.soft_window_cost)
+    }
+    #[getter]
+    fn overload(&self) -> Option<u64> {
+        self.capacity().map(|capacity| capacity.overload)
+    }
+    /// Indices of the pickup and delivery pairs that are not served by the same route, pickup first.
+    #[getter]
+    fn violated_pairs(&self) -> Option<Vec<usize>> {
+        self.capacity()
+            .map(|capacity| capacity.violated_pairs.clone())
     }
     #[getter]
     fn duration(&self) -> Option<u64> {
-        match &self.solution.time_report {
-            Some(time_report) => Some(time_report.duration.num_seconds() as u64),
-            None => None,
-        }
+        self.time().map(|time| time.duration)
     }
     #[getter]
     fn working_time(&self) -> Option<u64> {
-        match &self.solution.time_report {
-            Some(time_report) => Some(time_report.working_time.num_seconds() as u64),
-            None => None,
-        }
+        self.time().map(|time| time.working_time)
     }
     #[getter]
     fn waiting_time(&self) -> Option<u64> {
-        match &self.solution.time_report {
-            Some(time_report) => Some(time_report.waiting_time.num_seconds() as u64),
-            None => None,
-        }
+        self.time().map(|time| time.waiting_time)
     }
     #[getter]
     fn traveling_time(&self) -> Option<u64> {
-        match &self.solution.time_report {
-            Some(time_report) => Some(time_report.traveling_time.num_seconds() as u64),
-            None => None,
-        }
+        self.time().map(|time| time.traveling_time)
+    }
+    #[getter]
+    fn break_time(&self) -> Option<u64> {
+        self.time().map(|time| time.break_time)
     }
     #[getter]
     fn start_time(&self) -> Option<u64> {
-        match &self.solution.time_report {
-            Some(time_report) => Some(time_report.start_time.timestamp() as u64),
-            None => None,
-        }
+        self.time().map(|time| time.start_time)
     }
     #[getter]
     fn end_time(&self) -> Option<u64> {
-        match &self.solution.time_report {
-            Some(time_report) => Some(time_report.end_time.timestamp() as u64),
-            None => None,
-        }
+        self.time().map(|time| time.end_time)
+    }
+    /// The value of each level of the objective function, beginning with one per hard component.
+    #[getter]
+    fn objective(&self) -> Vec<f64> {
+        self.report.objective.clone()
+    }
+    /// Whether the solution is known to be optimal, since it was found exactly.
+    #[getter]
+    fn optimal(&self) -> bool {
+        self.report.optimal
     }
     #[getter]
     fn iterations(&self) -> u64 {
-        self.iterations
+        self.report.iterations
     }
     #[getter]
     fn time_taken_microseconds(&self) -> u64 {
-        self.time_taken.num_microseconds().unwrap() as u64
+        self.report.time_taken_microseconds
     }
+    /// The schedules of all vehicles, one after the other.
     #[getter]
     fn schedule(&self) -> Option<Vec<PyEvent>> {
-        match &self.solution.time_report {
-            Some(time_report) => Some(
-                time_report
-                    .schedule
-                    .iter()
-                    .map(|event| match event {
-                        Event::Work(window, location) => PyEvent::Work(PyWork {
-                            window: (
-                                window.start.timestamp() as u64,
-                                window.end.timestamp() as u64,
-                            ),
-                            location: *location,
-                        }),
-                        Event::Wait(window) => PyEvent::Wait(PyWait {
-                            window: (
-                                window.start.timestamp() as u64,
-                                window.end.timestamp() as u64,
-                            ),
-                        }),
-                        Event::Travel(window) => PyEvent::Travel(PyTravel {
-                            window: (
-                                window.start.timestamp() as u64,
-                                window.end.timestamp() as u64,
-                            ),
-                        }),
-                    })
-                    .collect(),
-            ),
-            None => None,
-        }
+        self.report
+            .routes
+            .iter()
+            .map(|route| route.time.as_ref().map(|time| py_schedule(&time.schedule)))
+            .collect::<Option<Vec<Vec<PyEvent>>>>()
+            .map(|schedules| schedules.into_iter().flatten().collect())
+    }
+    /// The solution with its full schedule as versioned JSON, to be read back by `from_json`.
+    fn to_json(&self) -> String {
+        self.report.to_json()
+    }
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<PyOutput> {
+        Ok(PyOutput {
+            report: SolutionReport::from_json(json)?,
+        })
+    }
+}
+
+/// The route and schedule of a single vehicle.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyRoute {
+    pub report: RouteReport,
+}
+
+impl PyRoute {
+    fn time(&self) -> Option<&TimeTotals> {
+        self.report.time.as_ref().map(|time| &time.totals)
+    }
+    fn capacity(&self) -> Option<&CapacityTotals> {
+        self.report
+            .capacity
+            .as_ref()
+            .map(|capacity| &capacity.totals)
+    }
+}
+
+#[pymethods]
+impl PyRoute {
+    #[getter]
+    fn route(&self) -> Vec<usize> {
+        self.report.route.clone()
+    }
+    #[getter]
+    fn start(&self) -> Option<usize> {
+        self.report.start
+    }
+    #[getter]
+    fn end(&self) -> Option<usize> {
+        self.report.end
+    }
+    #[getter]
+    fn distance(&self) -> u64 {
+        self.report.distance
+    }
+    #[getter]
+    fn lateness(&self) -> Option<u64> {
+        self.time().map(|time| time.lateness)
+    }
+    #[getter]
+    fn overtime(&self) -> Option<u64> {
+        self.time().map(|time| time.overtime)
+    }
+    #[getter]
+    fn excess(&self) -> Option<u64> {
+        self.time().map(|time| time.excess)
+    }
+    #[getter]
+    fn split_violations(&self) -> Option<u32> {
+        self.time().map(|time| time.split_violations)
+    }
+    #[getter]
+    fn soft_window_cost(&self) -> Option<f64> {
+        self.time().map(|time| time.soft_window_cost)
+    }
+    #[getter]
+    fn overload(&self) -> Option<u64> {
+        self.capacity().map(|capacity| capacity.overload)
+    }
+    /// Indices of the pickup and delivery pairs that are not served by the same route, pickup first.
+    #[getter]
+    fn violated_pairs(&self) -> Option<Vec<usize>> {
+        self.capacity()
+            .map(|capacity| capacity.violated_pairs.clone())
+    }
+    #[getter]
+    fn duration(&self) -> Option<u64> {
+        self.time().map(|time| time.duration)
+    }
+    #[getter]
+    fn working_time(&self) -> Option<u64> {
+        self.time().map(|time| time.working_time)
+    }
+    #[getter]
+    fn waiting_time(&self) -> Option<u64> {
+        self.time().map(|time| time.waiting_time)
+    }
+    #[getter]
+    fn traveling_time(&self) -> Option<u64> {
+        self.time().map(|time| time.traveling_time)
+    }
+    #[getter]
+    fn break_time(&self) -> Option<u64> {
+        self.time().map(|time| time.break_time)
+    }
+    #[getter]
+    fn start_time(&self) -> Option<u64> {
+        self.time().map(|time| time.start_time)
+    }
+    #[getter]
+    fn end_time(&self) -> Option<u64> {
+        self.time().map(|time| time.end_time)
+    }
+    #[getter]
+    fn schedule(&self) -> Option<Vec<PyEvent>> {
+        self.report
+            .time
+            .as_ref()
+            .map(|time| py_schedule(&time.schedule))
+    }
+    #[getter]
+    fn stops(&self) -> Vec<PyStop> {
+        py_stops(&self.report)
+    }
+    /// The load in each dimension when leaving the start and after each job.
+    #[getter]
+    fn loads(&self) -> Option<Vec<Vec<i64>>> {
+        self.report
+            .capacity
+            .as_ref()
+            .map(|capacity| capacity.loads.clone())
+    }
+    /// The jobs of the route that miss their preferred time windows.
+    #[getter]
+    fn penalties(&self) -> Vec<PyJobPenalty> {
+        self.report
+            .time
+            .iter()
+            .flat_map(|time| &time.penalties)
+            .map(|report| PyJobPenalty {
+                report: report.clone(),
+            })
+            .collect()
+    }
+}
+
+/// A job that misses the preferred time window of its location.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyJobPenalty {
+    pub report: PenaltyReport,
+}
+
+#[pymethods]
+impl PyJobPenalty {
+    #[getter]
+    fn location(&self) -> usize {
+        self.report.location
+    }
+    /// Seconds the job starts before the preferred time window.
+    #[getter]
+    fn earliness(&self) -> u64 {
+        self.report.earliness
+    }
+    /// Seconds the job ends after the preferred time window.
+    #[getter]
+    fn lateness(&self) -> u64 {
+        self.report.lateness
+    }
+    #[getter]
+    fn cost(&self) -> f64 {
+        self.report.cost
+    }
+}
+
+/// A precedence between two jobs that the routes violate, with durations in seconds.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyPrecedenceViolation {
+    pub report: PrecedenceReport,
+}
+
+#[pymethods]
+impl PyPrecedenceViolation {
+    /// Index of the precedence in the input.
+    #[getter]
+    fn precedence(&self) -> usize {
+        self.report.precedence
+    }
+    #[getter]
+    fn before(&self) -> usize {
+        self.report.before
+    }
+    #[getter]
+    fn after(&self) -> usize {
+        self.report.after
+    }
+    /// Time the later job starts before the minimum lag has passed.
+    #[getter]
+    fn early(&self) -> u64 {
+        self.report.early
+    }
+    /// Time the later job starts after the maximum lag has passed.
+    #[getter]
+    fn late(&self) -> u64 {
+        self.report.late
+    }
+}
+
+/// An optional job that the solver dropped.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyDroppedJob {
+    pub report: DroppedReport,
+}
+
+#[pymethods]
+impl PyDroppedJob {
+    #[getter]
+    fn location(&self) -> usize {
+        self.report.location
+    }
+    #[getter]
+    fn priority(&self) -> f64 {
+        self.report.priority
     }
+    /// The component of the objective that visiting the job would worsen the most,
+    /// e.g. "lateness" if it cannot be visited in time.
+    #[getter]
+    fn reason(&self) -> Option<String> {
+        self.report.reason.clone()
+    }
+}
+
+fn py_stops(report: &RouteReport) -> Vec<PyStop> {
+    report
+        .time
+        .iter()
+        .flat_map(|time| &time.stops)
+        .map(|report| PyStop {
+            report: report.clone(),
+        })
+        .collect()
+}
+
+/// The visit of a location, with timestamps and durations in seconds.
+#[pyclass]
+#[derive(Clone)]
+pub struct PyStop {
+    pub report: StopReport,
+}
+
+#[pymethods]
+impl PyStop {
+    #[getter]
+    fn location(&self) -> usize {
+        self.report.location
+    }
+    #[getter]
+    fn arrival(&self) -> u64 {
+        self.report.arrival
+    }
+    #[getter]
+    fn service_start(&self) -> u64 {
+        self.report.service_start
+    }
+    #[getter]
+    fn service_end(&self) -> u64 {
+        self.report.service_end
+    }
+    #[getter]
+    fn departure(&self) -> u64 {
+        self.report.departure
+    }
+    /// Waiting between the arrival and the start of the service.
+    #[getter]
+    fn waiting_time(&self) -> u64 {
+        self.report.waiting_time
+    }
+    #[getter]
+    fn lateness(&self) -> u64 {
+        self.report.lateness
+    }
+    /// Index of the time window of the location the service starts in, None if none.
+    #[getter]
+    fn time_window(&self) -> Option<usize> {
+        self.report.time_window
+    }
+    /// Whether the service had to be split.
+    #[getter]
+    fn split(&self) -> bool {
+        self.report.split
+    }
+}
+
+fn py_schedule(schedule: &[ScheduleEvent]) -> Vec<PyEvent> {
+    schedule
+        .iter()
+        .map(|event| match *event {
+            ScheduleEvent::Work { location, .. } => PyEvent::Work(PyWork {
+                window: event.window(),
+                location,
+            }),
+            ScheduleEvent::Wait { .. } => PyEvent::Wait(PyWait {
+                window: event.window(),
+            }),
+            ScheduleEvent::Travel { .. } => PyEvent::Travel(PyTravel {
+                window: event.window(),
+            }),
+            ScheduleEvent::Break { .. } => PyEvent::Break(PyBreak {
+                window: event.window(),
+            }),
+        })
+        .collect()
 }
 
 #[pyclass]
@@ -166,8 +522,37 @@ impl PyTravel {
     }
 }
 #[pyclass]
+#[derive(Clone)]
+pub struct PyBreak {
+    pub window: (u64, u64),
+}
+#[pymethods]
+impl PyBreak {
+    #[getter]
+    fn window(&self) -> (u64, u64) {
+        self.window
+    }
+}
+#[pyclass]
 enum PyEvent {
     Work(PyWork),
     Wait(PyWait),
     Travel(PyTravel),
+    Break(PyBreak),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_without_routes() {
+        let report = SolutionReport::from_json(
+            r#"{"distance": 0, "time": null, "routes": [], "iterations": 0, "time_taken_microseconds": 0}"#,
+        )
+        .unwrap();
+        let output = PyOutput { report };
+        assert!(output.route().is_empty());
+        assert!(output.routes().is_empty());
+    }
 }
@@ -1,17 +1,94 @@
 use std::ops::Index;
 
+/// A vehicle leaving from its start depot and returning to its end depot.
+/// Depots are not part of a route's sequence, so no job is executed there.
+/// Without a start depot, the route begins at its first job,
+/// without an end depot, it ends at its last job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vehicle {
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+}
+
+impl Vehicle {
+    pub fn new(start: usize, end: usize) -> Vehicle {
+        Vehicle {
+            start: Some(start),
+            end: Some(end),
+        }
+    }
+    pub fn with_endpoints(start: Option<usize>, end: Option<usize>) -> Vehicle {
+        Vehicle { start, end }
+    }
+    pub fn is_depot(&self, location: usize) -> bool {
+        self.start == Some(location) || self.end == Some(location)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Route {
     pub sequence: Vec<usize>,
+    /// Without a vehicle, the route is a closed tour over its sequence.
+    pub vehicle: Option<Vehicle>,
 }
 
 impl Route {
     pub fn new(sequence: Vec<usize>) -> Route {
-        Route { sequence }
+        Route {
+            sequence,
+            vehicle: None,
+        }
+    }
+    pub fn with_vehicle(sequence: Vec<usize>, vehicle: Vehicle) -> Route {
+        Route {
+            sequence,
+            vehicle: Some(vehicle),
+        }
     }
     pub fn len(&self) -> usize {
         self.sequence.len()
     }
+    pub fn is_empty(&self) -> bool {
+        self.sequence.is_empty()
+    }
+    /// The start depot of the route, if it has one.
+    pub fn start_depot(&self) -> Option<usize> {
+        self.vehicle.and_then(|vehicle| vehicle.start)
+    }
+    /// The end depot of the route, if it has one.
+    pub fn end_depot(&self) -> Option<usize> {
+        self.vehicle.and_then(|vehicle| vehicle.end)
+    }
+    /// The location where the schedule of the route starts.
+    /// An empty route without any depot has none.
+    pub fn first_location(&self) -> Option<usize> {
+        self.start_depot()
+            .or(self.sequence.first().copied())
+            .or(self.end_depot())
+    }
+    /// The location visited after the i-th location of the sequence.
+    /// An open route has none after its last location.
+    pub fn next_location(&self, i: usize) -> Option<usize> {
+        match (self.sequence.get(i + 1), self.vehicle) {
+            (Some(&next), _) => Some(next),
+            (None, Some(vehicle)) => vehicle.end,
+            (None, None) => Some(self.sequence[0]),
+        }
+    }
+    /// All pairs of locations the route travels between, in driving order.
+    /// An empty route of a vehicle does not travel at all.
+    pub fn legs(&self) -> Vec<(usize, usize)> {
+        let mut legs = Vec::with_capacity(self.len() + 1);
+        if let (Some(start), Some(&first)) = (self.start_depot(), self.sequence.first()) {
+            legs.push((start, first));
+        }
+        for (i, &location) in self.sequence.iter().enumerate() {
+            if let Some(next) = self.next_location(i) {
+                legs.push((location, next));
+            }
+        }
+        legs
+    }
 }
 impl Index<usize> for Route {
     type Output = usize;
@@ -20,3 +97,28 @@ impl Index<usize> for Route {
         &self.sequence[index]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legs() {
+        let route = Route::new(vec![0, 1, 2]);
+        assert_eq!(route.legs(), vec![(0, 1), (1, 2), (2, 0)]);
+        let route = Route::with_vehicle(vec![1, 2], Vehicle::new(0, 3));
+        assert_eq!(route.legs(), vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(route.first_location(), Some(0));
+        let route = Route::with_vehicle(vec![], Vehicle::new(0, 3));
+        assert!(route.legs().is_empty());
+        // an open route ends at its last job
+        let route = Route::with_vehicle(vec![1, 2], Vehicle::with_endpoints(Some(0), None));
+        assert_eq!(route.legs(), vec![(0, 1), (1, 2)]);
+        // without a start depot, it begins at its first job
+        let route = Route::with_vehicle(vec![1, 2], Vehicle::with_endpoints(None, Some(3)));
+        assert_eq!(route.legs(), vec![(1, 2), (2, 3)]);
+        assert_eq!(route.first_location(), Some(1));
+        let route = Route::with_vehicle(vec![1, 2], Vehicle::with_endpoints(None, None));
+        assert_eq!(route.legs(), vec![(1, 2)]);
+    }
+}
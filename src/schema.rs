@@ -0,0 +1,1080 @@
+// JSON representations of problems and solutions, shared by the command line tool and the Python bindings.
+// Times are unix timestamps and durations are seconds, just like in the Python interface.
+// Both representations carry a version, so archived problems and solutions can be replayed later on.
+
+use std::{collections::BTreeMap, fmt};
+
+use chrono::Timelike;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    acceptance::Acceptance,
+    input::{error::InputError, get_input_from_raw, Input},
+    objective::ObjectiveFunction,
+    output::{DroppedJob, Output, Solution},
+    penalties::{
+        capacity::capacity_output::CapacityOutput,
+        time::{
+            operation_times::{OperationTimes, Shift},
+            precedences::PrecedenceViolation,
+            soft_windows::{CostFunction, JobPenalty},
+            time_input::{RawPrecedence, RawSoftWindow, RawTimeWindows, SplitPolicy},
+            time_output::{Complete, Event, Stop, TimeOutput},
+        },
+    },
+};
+
+/// The version of the schema written by this crate. It is raised whenever an older version
+/// would misread a document, e.g. by ignoring options it does not know. Fields are only added
+/// with defaults, so documents of older versions can still be read.
+/// Problems and solutions without a version are read as version 1.
+/// - 1: the format of `data/example_input.json`.
+/// - 2: adds the options of the search, objectives, calendars, optional jobs, limits, start times,
+///   soft time windows, split policies, capacities, precedences and `exact`, as well as reports
+///   of stops, breaks, penalties, loads, dropped jobs, violations and optimality.
+pub const SCHEMA_VERSION: u32 = 2;
+
+fn first_version() -> u32 {
+    1
+}
+
+/// Solutions of older versions were not checked for hard constraints.
+fn feasible() -> bool {
+    true
+}
+
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The document is no valid JSON or does not match the schema.
+    Json(serde_json::Error),
+    /// The document was written by a newer version of this crate.
+    UnsupportedVersion { found: u32, supported: u32 },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Json(err) => write!(f, "{err}"),
+            SchemaError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "schema version {found} is not supported, the latest supported version is {supported}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl From<serde_json::Error> for SchemaError {
+    fn from(err: serde_json::Error) -> Self {
+        SchemaError::Json(err)
+    }
+}
+
+fn check_version(version: u32) -> Result<(), SchemaError> {
+    match version <= SCHEMA_VERSION {
+        true => Ok(()),
+        false => Err(SchemaError::UnsupportedVersion {
+            found: version,
+            supported: SCHEMA_VERSION,
+        }),
+    }
+}
+
+/// A problem, with the same fields as the arguments of `solve`.
+/// Every field but the distance matrix is optional.
+/// The time windows, the operation times and their calendar, the time zone, the breaks, the limits,
+/// start times, soft time windows, split policies and precedences are part of the schedule,
+/// so they need `duration_matrix` and `job_durations`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Problem {
+    #[serde(default = "first_version")]
+    pub version: u32,
+    /// Distance between each pair of locations.
+    pub distance_matrix: Vec<Vec<u64>>,
+    /// Travel duration between each pair of locations.
+    #[serde(default)]
+    pub duration_matrix: Option<Vec<Vec<u64>>>,
+    /// Duration of the job at each location.
+    #[serde(default, alias = "working_times")]
+    pub job_durations: Option<Vec<u64>>,
+    /// Chronologically ordered (start, end) time windows of each location,
+    /// `null` for a location that can be visited at any time.
+    /// Without them, every location can be visited at any time, starting at `start_time`.
+    #[serde(default)]
+    pub time_windows: Option<Vec<RawTimeWindows>>,
+    /// Daily (start, end) of work, as seconds since midnight, ending on the next day if it ends before it starts.
+    /// Without them, or with 24 hours every day, jobs and travel go on around the clock.
+    #[serde(default)]
+    pub operation_times: Option<(u64, u64)>,
+    /// Whether one works on each day of the week, starting on Monday.
+    #[serde(default)]
+    pub working_days: Option<Vec<bool>>,
+    /// (start, end) shifts of each weekday, starting on Monday, as seconds since midnight.
+    /// Replaces `operation_times` and `working_days`. A shift over midnight has to be the last one
+    /// of its day and has to end before the first shift of the next day starts.
+    #[serde(default)]
+    pub weekly_operation_times: Option<Vec<Vec<(u64, u64)>>>,
+    /// Shifts on dates given as "YYYY-MM-DD" instead of those of their weekday, none on a holiday.
+    #[serde(default)]
+    pub operation_time_exceptions: Option<BTreeMap<String, Vec<(u64, u64)>>>,
+    /// IANA name of the time zone of the operation times and working days, UTC if missing.
+    #[serde(default)]
+    pub time_zone: Option<String>,
+    /// Travel duration after which a break has to be taken.
+    #[serde(default)]
+    pub travel_duration_until_break: Option<u64>,
+    /// Duration of a break.
+    #[serde(default)]
+    pub break_duration: Option<u64>,
+    /// Time limit of the solver.
+    #[serde(default)]
+    pub time_limit: Option<u64>,
+    /// Route to start the search from.
+    #[serde(default)]
+    pub init_route: Option<Vec<usize>>,
+    /// (start, end) depots of each vehicle, among which the locations are split.
+    /// A depot of `None` lets the route begin at its first or end at its last job.
+    #[serde(default)]
+    pub vehicles: Option<Vec<(Option<usize>, Option<usize>)>>,
+    /// Maximum number of restarts of the solver.
+    #[serde(default)]
+    pub iteration_limit: Option<u64>,
+    /// Seed of the solver. With an iteration limit instead of a time limit, the result is reproducible.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Fixed start of a single route.
+    #[serde(default)]
+    pub start_location: Option<usize>,
+    /// Fixed end of a single route.
+    #[serde(default)]
+    pub end_location: Option<usize>,
+    /// Whether a single route ends at its last job.
+    #[serde(default)]
+    pub open_route: bool,
+    /// How the search continues after a local optimum: "improvement" restarts randomly,
+    /// "simulated_annealing" and "late_acceptance" perturb an accepted local optimum instead.
+    #[serde(default)]
+    pub acceptance: Option<String>,
+    /// Initial temperature of simulated annealing.
+    #[serde(default)]
+    pub initial_temperature: Option<f64>,
+    /// Factor of the temperature after each iteration of simulated annealing.
+    #[serde(default)]
+    pub cooling_rate: Option<f64>,
+    /// Number of past local optima late acceptance compares with.
+    #[serde(default)]
+    pub history_length: Option<usize>,
+    /// Levels of the objective, compared lexicographically,
+    /// each mapping the names of soft components to their weights, e.g. {"lateness": 1.0, "distance": 0.01}.
+    /// The hard components, from "split_violations" to "overtime", always precede them.
+    #[serde(default)]
+    pub objective: Option<Vec<BTreeMap<String, f64>>>,
+    /// Priority of each location, `None` for a job that has to be visited
+    /// and a value for an optional job, which may be dropped at the cost of its priority,
+    /// the "dropped_priority" component.
+    #[serde(default)]
+    pub priorities: Option<Vec<Option<f64>>>,
+    /// Timestamp by which every route has to end.
+    #[serde(default)]
+    pub horizon_end: Option<u64>,
+    /// Maximum duration of each route. Exceeding it or the horizon counts as "overtime".
+    /// Both are checked once a route is scheduled, so they penalize a route, but do not cut it off.
+    #[serde(default)]
+    pub max_route_duration: Option<u64>,
+    /// Whether exceeding the horizon or the maximum duration only counts as "excess", a soft component.
+    #[serde(default)]
+    pub soft_limits: bool,
+    /// Timestamp at which the vehicles are available, instead of the first opening of the first job.
+    /// With vehicles, a route starts at the start depot of its vehicle, which it otherwise leaves
+    /// just in time to reach the first job when it opens.
+    #[serde(default)]
+    pub start_time: Option<u64>,
+    /// Earliest local time of day at which a route departs, in seconds since midnight.
+    #[serde(default)]
+    pub earliest_departure: Option<u64>,
+    /// Preferred (start, end, earliness costs, lateness costs) of each location, `null` for none.
+    /// The costs are (deviation in seconds, cost) points of a piecewise-linear function.
+    /// Unlike for `time_windows`, the vehicle does not wait for them.
+    #[serde(default)]
+    pub soft_time_windows: Option<Vec<RawSoftWindow>>,
+    /// How the job of each location may be split when it does not fit: "allow" (the default),
+    /// "forbid" or "across_days" only at the end of a shift.
+    /// Splitting against the policy counts as a "split_violation" and makes the solution infeasible.
+    #[serde(default)]
+    pub split_policies: Option<Vec<String>>,
+    /// Minimum duration of each part of a split job of each location.
+    #[serde(default)]
+    pub min_split_durations: Option<Vec<u64>>,
+    /// What the job of each location delivers (positive) or picks up (negative), in each dimension.
+    /// A vehicle leaves with all its deliveries, except those of `pickups_deliveries`.
+    #[serde(default)]
+    pub demands: Option<Vec<Vec<i64>>>,
+    /// Capacity of each vehicle in each dimension.
+    #[serde(default)]
+    pub capacity: Option<Vec<u64>>,
+    /// (pickup, delivery) pairs of locations that have to be served by the same route, pickup first,
+    /// the delivery bringing what the pickup picked up: the demand of the pickup is the negated one
+    /// of the delivery, and not positive in any dimension. A location may be part of at most one pair,
+    /// and a depot of none.
+    #[serde(default)]
+    pub pickups_deliveries: Option<Vec<(usize, usize)>>,
+    /// (before, after, minimum lag, maximum lag) of jobs that have to be served one after the other,
+    /// with the lags in seconds from the end of the first to the start of the second job.
+    /// On the same route, the vehicle waits for the minimum lag. They only apply if both jobs are visited,
+    /// may not form a cycle and may not refer to depots.
+    #[serde(default)]
+    pub precedences: Option<Vec<RawPrecedence>>,
+    /// Whether to find the route exactly by dynamic programming instead of the local search,
+    /// which only applies when the distance of a single route over at most 18 locations is minimized,
+    /// without time or capacity constraints, priorities or an initial route.
+    #[serde(default)]
+    pub exact: bool,
+}
+
+/// A problem of the current version without any options, to be filled in with the given ones.
+impl Default for Problem {
+    fn default() -> Self {
+        Problem {
+            version: SCHEMA_VERSION,
+            distance_matrix: Default::default(),
+            duration_matrix: Default::default(),
+            job_durations: Default::default(),
+            time_windows: Default::default(),
+            operation_times: Default::default(),
+            working_days: Default::default(),
+            weekly_operation_times: Default::default(),
+            operation_time_exceptions: Default::default(),
+            time_zone: Default::default(),
+            travel_duration_until_break: Default::default(),
+            break_duration: Default::default(),
+            time_limit: Default::default(),
+            init_route: Default::default(),
+            vehicles: Default::default(),
+            iteration_limit: Default::default(),
+            seed: Default::default(),
+            start_location: Default::default(),
+            end_location: Default::default(),
+            open_route: Default::default(),
+            acceptance: Default::default(),
+            initial_temperature: Default::default(),
+            cooling_rate: Default::default(),
+            history_length: Default::default(),
+            objective: Default::default(),
+            priorities: Default::default(),
+            horizon_end: Default::default(),
+            max_route_duration: Default::default(),
+            soft_limits: Default::default(),
+            start_time: Default::default(),
+            earliest_departure: Default::default(),
+            soft_time_windows: Default::default(),
+            split_policies: Default::default(),
+            min_split_durations: Default::default(),
+            demands: Default::default(),
+            capacity: Default::default(),
+            pickups_deliveries: Default::default(),
+            precedences: Default::default(),
+            exact: Default::default(),
+        }
+    }
+}
+
+impl Problem {
+    pub fn from_json(json: &str) -> Result<Problem, SchemaError> {
+        let problem: Problem = serde_json::from_str(json)?;
+        check_version(problem.version)?;
+        Ok(problem)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn into_input(self) -> Result<Input, InputError> {
+        get_input_from_raw(self)
+    }
+}
+
+impl From<&Input> for Problem {
+    fn from(input: &Input) -> Self {
+        let seconds = |duration: &chrono::Duration| duration.num_seconds() as u64;
+        let time_input = input.time_input.as_ref();
+        let operation_times = time_input.and_then(|time_input| time_input.operation_times.as_ref());
+        let daily_shift = operation_times.and_then(OperationTimes::daily_shift);
+        let limits = time_input.map(|time_input| &time_input.limits);
+        let start = time_input.map(|time_input| &time_input.start);
+        let split_policies = time_input.map(|time_input| &time_input.split_policies);
+        let capacity_input = input.capacity_input.as_ref();
+        let shifts = |shifts: &[Shift]| -> Vec<(u64, u64)> {
+            shifts
+                .iter()
+                .map(|shift| {
+                    let (start, end) = shift.times();
+                    (start as u64, end as u64)
+                })
+                .collect()
+        };
+        Problem {
+            version: SCHEMA_VERSION,
+            distance_matrix: input.distance_matrix.matrix().to_vec(),
+            duration_matrix: time_input.map(|time_input| {
+                time_input
+                    .duration_matrix
+                    .iter()
+                    .map(|row| row.iter().map(seconds).collect())
+                    .collect()
+            }),
+            job_durations: time_input
+                .map(|time_input| time_input.job_durations.iter().map(seconds).collect()),
+            time_windows: time_input.map(|time_input| {
+                time_input
+                    .time_windows
+                    .iter()
+                    .map(|time_windows| {
+                        (!time_windows.is_empty()).then(|| {
+                            time_windows
+                                .windows
+                                .iter()
+                                .map(|window| {
+                                    (
+                                        window.start.timestamp() as u64,
+                                        window.end.timestamp() as u64,
+                                    )
+                                })
+                                .collect()
+                        })
+                    })
+                    .collect()
+            }),
+            operation_times: daily_shift.map(|shift| {
+                let (start, end) = shift.times();
+                (start as u64, end as u64)
+            }),
+            working_days: daily_shift
+                .and(operation_times)
+                .map(|operation_times| operation_times.working_days())
+                .filter(|days| days.len() < 7)
+                .map(|days| {
+                    (0..7)
+                        .map(|day| {
+                            days.iter()
+                                .any(|weekday| weekday.num_days_from_monday() == day)
+                        })
+                        .collect()
+                }),
+            weekly_operation_times: operation_times.filter(|_| daily_shift.is_none()).map(
+                |operation_times| {
+                    operation_times
+                        .weekly_shifts()
+                        .iter()
+                        .map(|day| shifts(day))
+                        .collect()
+                },
+            ),
+            operation_time_exceptions: operation_times
+                .map(|operation_times| operation_times.exceptions())
+                .filter(|exceptions| !exceptions.is_empty())
+                .map(|exceptions| {
+                    exceptions
+                        .iter()
+                        .map(|(date, day)| (date.format("%Y-%m-%d").to_string(), shifts(day)))
+                        .collect()
+                }),
+            time_zone: operation_times
+                .map(|operation_times| operation_times.time_zone())
+                .or(start.map(|start| start.time_zone))
+                .filter(|&time_zone| time_zone != chrono_tz::Tz::UTC)
+                .map(|time_zone| time_zone.name().to_string()),
+            travel_duration_until_break: time_input
+                .and_then(|time_input| time_input.travel_duration_until_break.as_ref())
+                .map(seconds),
+            break_duration: time_input
+                .and_then(|time_input| time_input.break_duration.as_ref())
+                .map(seconds),
+            time_limit: input.time_limit.as_ref().map(seconds),
+            init_route: input
+                .init_route
+                .as_ref()
+                .map(|route| route.sequence.clone()),
+            vehicles: input.vehicles.as_ref().map(|vehicles| {
+                vehicles
+                    .iter()
+                    .map(|vehicle| (vehicle.start, vehicle.end))
+                    .collect()
+            }),
+            iteration_limit: input.iteration_limit,
+            seed: input.seed,
+            start_location: None,
+            end_location: None,
+            open_route: false,
+            acceptance: match input.acceptance {
+                Acceptance::Improvement => None,
+                Acceptance::SimulatedAnnealing { .. } => Some("simulated_annealing".to_string()),
+                Acceptance::LateAcceptance { .. } => Some("late_acceptance".to_string()),
+            },
+            initial_temperature: match input.acceptance {
+                Acceptance::SimulatedAnnealing {
+                    initial_temperature,
+                    ..
+                } => Some(initial_temperature),
+                _ => None,
+            },
+            cooling_rate: match input.acceptance {
+                Acceptance::SimulatedAnnealing { cooling_rate, .. } => Some(cooling_rate),
+                _ => None,
+            },
+            history_length: match input.acceptance {
+                Acceptance::LateAcceptance { history_length } => Some(history_length),
+                _ => None,
+            },
+            objective: (input.objective != ObjectiveFunction::default()).then(|| {
+                input
+                    .objective
+                    .levels()
+                    .iter()
+                    .map(|level| {
+                        level
+                            .iter()
+                            .map(|(component, weight)| (component.name().to_string(), *weight))
+                            .collect()
+                    })
+                    .collect()
+            }),
+            priorities: input.priorities.clone(),
+            horizon_end: limits
+                .and_then(|limits| limits.horizon_end)
+                .map(|horizon_end| horizon_end.timestamp() as u64),
+            max_route_duration: limits
+                .and_then(|limits| limits.max_duration.as_ref())
+                .map(seconds),
+            soft_limits: limits.is_some_and(|limits| limits.soft),
+            start_time: start
+                .and_then(|start| start.time)
+                .map(|start_time| start_time.timestamp() as u64),
+            earliest_departure: start
+                .and_then(|start| start.earliest_departure)
+                .map(|time| time.num_seconds_from_midnight() as u64),
+            soft_time_windows: time_input
+                .filter(|time_input| !time_input.soft_windows.is_empty())
+                .map(|time_input| {
+                    time_input
+                        .soft_windows
+                        .iter()
+                        .map(|soft_window| {
+                            soft_window.as_ref().map(|soft_window| {
+                                let points = |costs: &CostFunction| {
+                                    costs
+                                        .points()
+                                        .iter()
+                                        .map(|(deviation, cost)| (seconds(deviation), *cost))
+                                        .collect()
+                                };
+                                (
+                                    soft_window.window.start.timestamp() as u64,
+                                    soft_window.window.end.timestamp() as u64,
+                                    points(&soft_window.earliness),
+                                    points(&soft_window.lateness),
+                                )
+                            })
+                        })
+                        .collect()
+                }),
+            split_policies: split_policies
+                .filter(|policies| {
+                    policies
+                        .iter()
+                        .any(|policy| !matches!(policy, SplitPolicy::Allow { .. }))
+                })
+                .map(|policies| {
+                    policies
+                        .iter()
+                        .map(|policy| policy.name().to_string())
+                        .collect()
+                }),
+            min_split_durations: split_policies
+                .filter(|policies| {
+                    policies
+                        .iter()
+                        .any(|policy| policy.min_part() > chrono::Duration::zero())
+                })
+                .map(|policies| {
+                    policies
+                        .iter()
+                        .map(|policy| seconds(&policy.min_part()))
+                        .collect()
+                }),
+            demands: capacity_input
+                .map(|capacity_input| capacity_input.demands.clone())
+                .filter(|demands| !demands.is_empty()),
+            capacity: capacity_input.and_then(|capacity_input| capacity_input.capacity.clone()),
+            pickups_deliveries: capacity_input
+                .map(|capacity_input| capacity_input.pickups_deliveries.clone())
+                .filter(|pairs| !pairs.is_empty()),
+            precedences: time_input
+                .filter(|time_input| !time_input.precedences.is_empty())
+                .map(|time_input| {
+                    time_input
+                        .precedences
+                        .iter()
+                        .map(|precedence| {
+                            (
+                                precedence.before,
+                                precedence.after,
+                                Some(seconds(&precedence.min_lag)).filter(|&min_lag| min_lag > 0),
+                                precedence.max_lag.as_ref().map(seconds),
+                            )
+                        })
+                        .collect()
+                }),
+            exact: input.exact,
+        }
+    }
+}
+
+/// The solution of all vehicles together.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolutionReport {
+    #[serde(default = "first_version")]
+    pub version: u32,
+    /// Total distance of all routes.
+    pub distance: u64,
+    /// Value of each level of the objective function, beginning with one per hard component.
+    #[serde(default)]
+    pub objective: Vec<f64>,
+    /// Whether the routes keep the planning horizon, the maximum route duration,
+    /// the split policies of their jobs, the precedences, the capacity and the pickup and delivery pairs.
+    #[serde(default = "feasible")]
+    pub feasible: bool,
+    /// Totals of the schedules of all routes, if there were time constraints.
+    pub time: Option<TimeTotals>,
+    /// Totals of the loads of all routes, if there were capacity constraints.
+    #[serde(default)]
+    pub capacity: Option<CapacityTotals>,
+    /// One route per vehicle, or the single tour without vehicles.
+    pub routes: Vec<RouteReport>,
+    /// Optional jobs that are not part of any route.
+    #[serde(default)]
+    pub dropped: Vec<DroppedReport>,
+    /// Precedences between jobs that the routes violate.
+    #[serde(default)]
+    pub precedence_violations: Vec<PrecedenceReport>,
+    /// Whether the solution is known to be optimal, since it was found exactly.
+    #[serde(default)]
+    pub optimal: bool,
+    /// Number of restarts of the solver.
+    pub iterations: u64,
+    pub time_taken_microseconds: u64,
+}
+
+impl SolutionReport {
+    pub fn from_json(json: &str) -> Result<SolutionReport, SchemaError> {
+        let report: SolutionReport = serde_json::from_str(json)?;
+        check_version(report.version)?;
+        Ok(report)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// The route and schedule of a single vehicle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteReport {
+    /// Locations in the order they are visited, without the depots.
+    pub route: Vec<usize>,
+    /// Start depot of the vehicle.
+    pub start: Option<usize>,
+    /// End depot of the vehicle.
+    pub end: Option<usize>,
+    pub distance: u64,
+    pub time: Option<TimeReport>,
+    #[serde(default)]
+    pub capacity: Option<CapacityReport>,
+}
+
+/// An optional job that is not visited.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DroppedReport {
+    pub location: usize,
+    pub priority: f64,
+    /// Name of the component of the objective that visiting the job would worsen the most.
+    pub reason: Option<String>,
+}
+
+/// How far the later job of a precedence starts too early or too late, in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrecedenceReport {
+    /// Index of the precedence.
+    pub precedence: usize,
+    pub before: usize,
+    pub after: usize,
+    /// Time the later job starts before the minimum lag has passed.
+    pub early: u64,
+    /// Time the later job starts after the maximum lag has passed.
+    pub late: u64,
+}
+
+impl From<&PrecedenceViolation> for PrecedenceReport {
+    fn from(violation: &PrecedenceViolation) -> Self {
+        PrecedenceReport {
+            precedence: violation.precedence,
+            before: violation.before,
+            after: violation.after,
+            early: violation.early.num_seconds() as u64,
+            late: violation.late.num_seconds() as u64,
+        }
+    }
+}
+
+/// Totals of a schedule, all durations in seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeTotals {
+    pub start_time: u64,
+    pub end_time: u64,
+    pub duration: u64,
+    pub lateness: u64,
+    pub working_time: u64,
+    pub waiting_time: u64,
+    pub traveling_time: u64,
+    pub break_time: u64,
+    /// Number of jobs that had to be split.
+    pub job_splits: u32,
+    /// Number of jobs that had to be split against their split policy.
+    #[serde(default)]
+    pub split_violations: u32,
+    /// Time beyond the planning horizon or the maximum duration of the route.
+    #[serde(default)]
+    pub overtime: u64,
+    /// Like `overtime`, if the limits are soft.
+    #[serde(default)]
+    pub excess: u64,
+    /// Total cost of missing the preferred time windows.
+    #[serde(default)]
+    pub soft_window_cost: f64,
+}
+
+/// How far the loads exceed the capacity and which pickup and delivery pairs are violated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapacityTotals {
+    /// Total amount by which the loads exceed the capacity, when leaving the start and after each job.
+    pub overload: u64,
+    /// Indices of the pairs that are not served by the same route, pickup first.
+    pub violated_pairs: Vec<usize>,
+}
+
+/// The loads of a single route with its totals.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapacityReport {
+    #[serde(flatten)]
+    pub totals: CapacityTotals,
+    /// Load in each dimension when leaving the start and after each job.
+    pub loads: Vec<Vec<i64>>,
+}
+
+impl From<&CapacityOutput> for CapacityTotals {
+    fn from(capacity_output: &CapacityOutput) -> Self {
+        CapacityTotals {
+            overload: capacity_output.overload,
+            violated_pairs: capacity_output.violated_pairs.clone(),
+        }
+    }
+}
+
+impl From<&CapacityOutput> for CapacityReport {
+    fn from(capacity_output: &CapacityOutput) -> Self {
+        CapacityReport {
+            totals: capacity_output.into(),
+            loads: capacity_output.loads.clone(),
+        }
+    }
+}
+
+/// The schedule of a single route with its totals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeReport {
+    #[serde(flatten)]
+    pub totals: TimeTotals,
+    pub schedule: Vec<ScheduleEvent>,
+    /// The jobs that miss their preferred time windows.
+    #[serde(default)]
+    pub penalties: Vec<PenaltyReport>,
+    /// The visits of the route, in order.
+    #[serde(default)]
+    pub stops: Vec<StopReport>,
+}
+
+/// The visit of a location, with timestamps and durations in seconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StopReport {
+    pub location: usize,
+    pub arrival: u64,
+    pub service_start: u64,
+    pub service_end: u64,
+    pub departure: u64,
+    /// Waiting between the arrival and the start of the service.
+    pub waiting_time: u64,
+    pub lateness: u64,
+    /// Index of the time window the service starts in, `null` if none.
+    pub time_window: Option<usize>,
+    /// Whether the service had to be split.
+    pub split: bool,
+}
+
+impl From<&Stop> for StopReport {
+    fn from(stop: &Stop) -> Self {
+        StopReport {
+            location: stop.location,
+            arrival: stop.arrival.timestamp() as u64,
+            service_start: stop.service_start.timestamp() as u64,
+            service_end: stop.service_end.timestamp() as u64,
+            departure: stop.departure.timestamp() as u64,
+            waiting_time: stop.waiting_time.num_seconds() as u64,
+            lateness: stop.lateness.num_seconds() as u64,
+            time_window: stop.time_window,
+            split: stop.split,
+        }
+    }
+}
+
+/// How far a job misses the preferred time window of its location, in seconds, and what it costs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PenaltyReport {
+    pub location: usize,
+    pub earliness: u64,
+    pub lateness: u64,
+    pub cost: f64,
+}
+
+impl From<&JobPenalty> for PenaltyReport {
+    fn from(penalty: &JobPenalty) -> Self {
+        PenaltyReport {
+            location: penalty.location,
+            earliness: penalty.earliness.num_seconds() as u64,
+            lateness: penalty.lateness.num_seconds() as u64,
+            cost: penalty.cost,
+        }
+    }
+}
+
+/// One event of a schedule, tagged by its `type`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleEvent {
+    /// Working on the job at the location.
+    Work {
+        start: u64,
+        end: u64,
+        location: usize,
+    },
+    Travel {
+        start: u64,
+        end: u64,
+    },
+    Wait {
+        start: u64,
+        end: u64,
+    },
+    /// A mandatory break after travelling for too long.
+    Break {
+        start: u64,
+        end: u64,
+    },
+}
+
+impl ScheduleEvent {
+    pub fn window(&self) -> (u64, u64) {
+        match *self {
+            ScheduleEvent::Work { start, end, .. }
+            | ScheduleEvent::Travel { start, end }
+            | ScheduleEvent::Wait { start, end }
+            | ScheduleEvent::Break { start, end } => (start, end),
+        }
+    }
+}
+
+impl From<&TimeOutput<Complete>> for TimeTotals {
+    fn from(time_output: &TimeOutput<Complete>) -> Self {
+        TimeTotals {
+            start_time: time_output.start_time.timestamp() as u64,
+            end_time: time_output.end_time.timestamp() as u64,
+            duration: time_output.duration.num_seconds() as u64,
+            lateness: time_output.lateness.num_seconds() as u64,
+            working_time: time_output.working_time.num_seconds() as u64,
+            waiting_time: time_output.waiting_time.num_seconds() as u64,
+            traveling_time: time_output.traveling_time.num_seconds() as u64,
+            break_time: time_output.break_time.num_seconds() as u64,
+            job_splits: time_output.job_splits,
+            split_violations: time_output.split_violations,
+            overtime: time_output.overtime.num_seconds() as u64,
+            excess: time_output.excess.num_seconds() as u64,
+            soft_window_cost: time_output.soft_window_cost,
+        }
+    }
+}
+
+impl From<&Event> for ScheduleEvent {
+    fn from(event: &Event) -> Self {
+        match event {
+            Event::Work(window, location) => ScheduleEvent::Work {
+                start: window.start.timestamp() as u64,
+                end: window.end.timestamp() as u64,
+                location: *location,
+            },
+            Event::Travel(window) => ScheduleEvent::Travel {
+                start: window.start.timestamp() as u64,
+                end: window.end.timestamp() as u64,
+            },
+            Event::Wait(window) => ScheduleEvent::Wait {
+                start: window.start.timestamp() as u64,
+                end: window.end.timestamp() as u64,
+            },
+            Event::Break(window) => ScheduleEvent::Break {
+                start: window.start.timestamp() as u64,
+                end: window.end.timestamp() as u64,
+            },
+        }
+    }
+}
+
+impl From<&TimeOutput<Complete>> for TimeReport {
+    fn from(time_output: &TimeOutput<Complete>) -> Self {
+        TimeReport {
+            totals: time_output.into(),
+            schedule: time_output
+                .schedule
+                .iter()
+                .map(ScheduleEvent::from)
+                .collect(),
+            penalties: time_output
+                .penalties
+                .iter()
+                .map(PenaltyReport::from)
+                .collect(),
+            stops: time_output.stops.iter().map(StopReport::from).collect(),
+        }
+    }
+}
+
+impl From<&Solution> for RouteReport {
+    fn from(solution: &Solution) -> Self {
+        RouteReport {
+            route: solution.route.sequence.clone(),
+            start: solution.route.start_depot(),
+            end: solution.route.end_depot(),
+            distance: solution.distance,
+            time: solution.time_report.as_ref().map(TimeReport::from),
+            capacity: solution.capacity_report.as_ref().map(CapacityReport::from),
+        }
+    }
+}
+
+impl From<&DroppedJob> for DroppedReport {
+    fn from(dropped: &DroppedJob) -> Self {
+        DroppedReport {
+            location: dropped.location,
+            priority: dropped.priority,
+            reason: dropped.reason.map(|component| component.name().to_string()),
+        }
+    }
+}
+
+impl From<&Output> for SolutionReport {
+    fn from(output: &Output) -> Self {
+        SolutionReport {
+            version: SCHEMA_VERSION,
+            distance: output.solution.distance,
+            objective: output.objective.clone(),
+            feasible: output
+                .solution
+                .time_report
+                .as_ref()
+                .is_none_or(TimeOutput::is_feasible)
+                && output.precedence_violations.is_empty()
+                && output
+                    .solution
+                    .capacity_report
+                    .as_ref()
+                    .is_none_or(CapacityOutput::is_feasible),
+            time: output.solution.time_report.as_ref().map(TimeTotals::from),
+            capacity: output
+                .solution
+                .capacity_report
+                .as_ref()
+                .map(CapacityTotals::from),
+            routes: output
+                .solution
+                .routes
+                .iter()
+                .map(RouteReport::from)
+                .collect(),
+            dropped: output.dropped.iter().map(DroppedReport::from).collect(),
+            precedence_violations: output
+                .precedence_violations
+                .iter()
+                .map(PrecedenceReport::from)
+                .collect(),
+            optimal: output.optimal,
+            iterations: output.iterations,
+            time_taken_microseconds: output.time_taken.num_microseconds().unwrap_or(0) as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        objective::{Component, COMPONENTS},
+        solver::Solver,
+    };
+
+    #[test]
+    fn test_example_input() {
+        let problem = Problem::from_json(include_str!("../data/example_input.json")).unwrap();
+        assert_eq!(problem.version, 1);
+        assert_eq!(problem.distance_matrix.len(), 13);
+        assert_eq!(problem.job_durations.as_ref().unwrap().len(), 13);
+        assert_eq!(problem.operation_times, Some((28800, 72000)));
+        assert!(problem.into_input().is_ok());
+    }
+
+    #[test]
+    fn test_problem_round_trip() {
+        let problem = Problem::from_json(
+            r#"{
+                "version": 2,
+                "distance_matrix": [[0, 1], [1, 0]],
+                "duration_matrix": [[0, 3600], [3600, 0]],
+                "job_durations": [3600, 3600],
+                "time_windows": [[[1735722000, 1735754400]], null],
+                "operation_times": [28800, 72000],
+                "working_days": [true, false, true, false, true, false, false],
+                "time_zone": "Europe/Berlin",
+                "travel_duration_until_break": 7200,
+                "break_duration": 1800,
+                "time_limit": 3,
+                "init_route": [1, 0],
+                "seed": 1,
+                "acceptance": "simulated_annealing",
+                "initial_temperature": 0.1,
+                "cooling_rate": 0.9,
+                "objective": [{"job_splits": 1.0}, {"lateness": 0.5, "distance": 0.001}],
+                "priorities": [null, 2.5],
+                "horizon_end": 1736035200,
+                "max_route_duration": 172800,
+                "soft_limits": true,
+                "start_time": 1735714800,
+                "earliest_departure": 25200,
+                "soft_time_windows": [null, [1735729200, 1735736400, [[900, 0.0], [3600, 2.0]], [[3600, 1.5]]]],
+                "split_policies": ["forbid", "across_days"],
+                "min_split_durations": [0, 7200],
+                "demands": [[-2, -1], [2, 1]],
+                "capacity": [4, 2],
+                "pickups_deliveries": [[0, 1]],
+                "precedences": [[0, 1, 3600, 7200]],
+                "exact": false
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(Problem::from_json(&problem.to_json()).unwrap(), problem);
+        // archiving the input of the solver gives back the same problem
+        let input = problem.clone().into_input().unwrap();
+        assert_eq!(Problem::from(&input), problem);
+        // so does a calendar with different shifts per weekday and holidays
+        let problem = Problem::from_json(
+            r#"{
+                "version": 2,
+                "distance_matrix": [[0, 1], [1, 0]],
+                "duration_matrix": [[0, 3600], [3600, 0]],
+                "job_durations": [3600, 3600],
+                "time_windows": [[[1735722000, 1735754400]], [[1735722000, 1735754400]]],
+                "weekly_operation_times": [
+                    [[25200, 57600]], [[25200, 57600]], [[25200, 57600]], [[25200, 57600]],
+                    [[25200, 43200]], [], []
+                ],
+                "operation_time_exceptions": {"2025-01-01": [], "2025-01-04": [[28800, 36000], [50400, 64800]]}
+            }"#,
+        )
+        .unwrap();
+        let input = problem.clone().into_input().unwrap();
+        assert_eq!(Problem::from(&input), problem);
+        assert!(matches!(
+            Problem::from_json(r#"{"version": 3, "distance_matrix": [[0]]}"#),
+            Err(SchemaError::UnsupportedVersion {
+                found: 3,
+                supported: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_solution_report() {
+        let problem = Problem::from_json(
+            r#"{
+                "distance_matrix": [[0, 1], [1, 0]],
+                "duration_matrix": [[0, 3600], [3600, 0]],
+                "job_durations": [3600, 3600],
+                "time_windows": [[[1735722000, 1735754400]], [[1735722000, 1735754400]]],
+                "operation_times": [28800, 72000],
+                "seed": 1
+            }"#,
+        )
+        .unwrap();
+        let mut solver = Solver::new(problem.into_input().unwrap());
+        solver.solve();
+        let output = Output {
+            solution: solver.best_solution.clone(),
+            objective: solver.objective(),
+            dropped: solver.dropped_jobs(),
+            precedence_violations: solver.precedence_violations(),
+            optimal: solver.optimal,
+            iterations: solver.iterations,
+            time_taken: solver.time_taken,
+        };
+        let report = SolutionReport::from(&output);
+        assert_eq!(
+            SolutionReport::from_json(&report.to_json()).unwrap(),
+            report
+        );
+        let report = serde_json::to_value(report).unwrap();
+        assert_eq!(report["version"], SCHEMA_VERSION);
+        assert_eq!(report["distance"], 2);
+        let objective = report["objective"].as_array().unwrap();
+        assert_eq!(objective.len(), COMPONENTS);
+        assert_eq!(objective[Component::Distance.index()], 2.0);
+        // nothing is dropped, which is zero, not negative zero
+        assert_eq!(
+            objective[Component::DroppedPriority.index()].to_string(),
+            "0.0"
+        );
+        assert!(report["dropped"].as_array().unwrap().is_empty());
+        assert_eq!(report["time"]["working_time"], 7200);
+        assert_eq!(report["time"]["traveling_time"], 7200);
+        let schedule = report["routes"][0]["time"]["schedule"].as_array().unwrap();
+        assert_eq!(schedule.len(), 4);
+        assert_eq!(schedule[0]["type"], "work");
+        assert_eq!(schedule[0]["start"], 1735722000);
+        assert_eq!(schedule[1]["type"], "travel");
+        let stops = report["routes"][0]["time"]["stops"].as_array().unwrap();
+        assert_eq!(stops.len(), 2);
+        assert_eq!(stops[0]["arrival"], 1735722000);
+        assert_eq!(stops[0]["departure"], 1735725600);
+        assert_eq!(stops[1]["arrival"], 1735729200);
+        assert_eq!(stops[1]["split"], false);
+        // reports of the first version are still read, with or without their version
+        let report = SolutionReport::from_json(
+            r#"{
+                "distance": 2,
+                "time": null,
+                "routes": [{"route": [0, 1], "start": null, "end": null, "distance": 2, "time": null}],
+                "iterations": 1,
+                "time_taken_microseconds": 10
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(report.version, 1);
+        assert!(report.feasible);
+        assert!(report.objective.is_empty());
+    }
+}
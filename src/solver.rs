@@ -1,26 +1,53 @@
 use chrono::TimeDelta;
 
 use crate::{
+    acceptance::{Acceptance, Acceptor},
+    exact,
     input::Input,
     local_moves::{
-        one_shift_left, one_shift_right, swap, three_shift_left, three_shift_right, two_opt,
-        two_shift_left, two_shift_right,
+        cross, double_bridge, exchange, insert, one_shift_left, one_shift_right, relocate, remove,
+        reversed_shift_left, reversed_shift_right, swap, three_opt, three_shift_left,
+        three_shift_right, two_opt, two_shift_left, two_shift_right, ThreeOpt,
     },
-    output::Solution,
+    output::{DroppedJob, MultiSolution},
     penalizer::Penalizer,
-    penalties::{distance::DistancePenalizer, time::TimePenalizer},
-    route::Route,
+    penalties::{
+        capacity::CapacityPenalizer,
+        distance::{DistanceCache, DistancePenalizer},
+        time::{precedences::PrecedenceViolation, TimePenalizer},
+    },
+    route::{Route, Vehicle},
 };
 
-use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::seq::{index, SliceRandom};
+use rand::{Rng, SeedableRng};
+
+/// The longest segment a 3-opt move reverses or moves.
+const THREE_OPT_MAX_SEGMENT: usize = 16;
+
+/// The changes of distance of both routes of an inter-route move at positions i and j.
+type InterDistanceDelta =
+    dyn Fn(&DistancePenalizer, &DistanceCache, &DistanceCache, usize, usize) -> [i64; 2];
 
 pub struct Solver {
-    n: usize,
+    /// The locations that can be visited, i.e. all locations except the depots.
+    jobs: Vec<usize>,
+    vehicles: Option<Vec<Vehicle>>,
     penalizer: Penalizer,
-    current_solution: Solution,
-    pub best_solution: Solution,
+    current_solution: MultiSolution,
+    /// The local optimum the search continues from, unless it restarts randomly.
+    accepted_solution: Option<MultiSolution>,
+    acceptor: Acceptor,
+    pub best_solution: MultiSolution,
     time_limit: Option<TimeDelta>,
+    iteration_limit: Option<u64>,
+    /// Whether the route is found exactly by dynamic programming instead of the local search.
+    exact: bool,
+    /// Whether the best solution is known to be optimal.
+    pub optimal: bool,
+    /// Every random decision is drawn from this generator, so seeded runs are reproducible.
+    rng: StdRng,
     start: chrono::DateTime<chrono::Utc>,
     pub iterations: u64,
     pub time_taken: chrono::Duration,
@@ -31,85 +58,572 @@ impl Solver {
         let n = input.distance_matrix.len();
         let distance_matrix = input.distance_matrix;
         let time_limit = input.time_limit;
+        let iteration_limit = input.iteration_limit;
+        let rng = match input.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let vehicles = input.vehicles;
+        let jobs: Vec<usize> = match &vehicles {
+            Some(vehicles) => (0..n)
+                .filter(|&location| !is_depot(location, vehicles))
+                .collect(),
+            None => (0..n).collect(),
+        };
         let distance_penalizer = DistancePenalizer::new(distance_matrix);
-        let time_penalizer = input
-            .time_input
-            .map(|time_input| TimePenalizer::new(time_input));
-        let penalizer: Penalizer = Penalizer::new(distance_penalizer, time_penalizer);
-        let route = match input.init_route {
-            Some(route) => route,
-            None => Route::new((0..n).collect()),
+        let time_penalizer = input.time_input.map(TimePenalizer::new);
+        let penalizer = Penalizer {
+            capacity_penalizer: input.capacity_input.map(CapacityPenalizer::new),
+            objective_function: input.objective,
+            priorities: input.priorities.unwrap_or_default(),
+            ..Penalizer::new(distance_penalizer, time_penalizer)
+        };
+        let sequence = match input.init_route {
+            Some(route) => route.sequence,
+            None => (0..n).collect(),
+        };
+        // optional jobs missing in the initial route start out dropped
+        let mut visited = vec![false; n];
+        for &location in &sequence {
+            visited[location] = true;
+        }
+        let dropped = jobs.iter().copied().filter(|&job| !visited[job]).collect();
+        let current_solution = MultiSolution {
+            dropped,
+            ..penalizer.penalize_routes(split_sequence(sequence, &vehicles), false)
         };
-        let current_solution = penalizer.penalize(route, false);
         let best_solution = current_solution.clone();
         let start = chrono::Utc::now();
         Solver {
-            n,
+            jobs,
+            vehicles,
             penalizer,
             current_solution,
+            accepted_solution: None,
+            acceptor: Acceptor::new(input.acceptance),
             best_solution,
             time_limit,
+            iteration_limit,
+            exact: input.exact,
+            optimal: false,
+            rng,
             start,
             iterations: 0,
             time_taken: chrono::Duration::zero(),
         }
     }
 
-    fn generate_initial_solution(&self) -> Solution {
-        let mut sequence = (0..=self.n - 1).collect::<Vec<usize>>();
-        sequence.shuffle(&mut thread_rng());
-        let route = Route::new(sequence);
-        self.penalizer.penalize(route, false)
+    fn generate_initial_solution(&mut self) -> MultiSolution {
+        let mut sequence = self.jobs.clone();
+        sequence.shuffle(&mut self.rng);
+        self.penalizer
+            .penalize_routes(split_sequence(sequence, &self.vehicles), false)
+    }
+
+    /// The solution to start the next local search from.
+    fn next_solution(&mut self) -> MultiSolution {
+        if self.acceptor.acceptance() == Acceptance::Improvement {
+            return self.generate_initial_solution();
+        }
+        let candidate = self.current_solution.clone();
+        let accepted = match &self.accepted_solution {
+            None => true,
+            Some(accepted_solution) => self.acceptor.accept(
+                &self.penalizer.objective_multi(&candidate),
+                &self.penalizer.objective_multi(accepted_solution),
+                &mut self.rng,
+            ),
+        };
+        if accepted {
+            self.accepted_solution = Some(candidate);
+        }
+        let routes = self
+            .accepted_solution
+            .iter()
+            .flat_map(|solution| solution.routes.iter())
+            .map(|solution| solution.route.clone())
+            .collect();
+        let dropped = self
+            .accepted_solution
+            .as_ref()
+            .map_or(vec![], |solution| solution.dropped.clone());
+        self.perturb(routes, dropped)
+    }
+
+    /// Exchanges two random neighbouring segments of every route,
+    /// moves a random location to another route and puts a random dropped job back.
+    fn perturb(&mut self, mut routes: Vec<Route>, mut dropped: Vec<usize>) -> MultiSolution {
+        for route in routes.iter_mut().filter(|route| route.len() >= 2) {
+            let mut cuts = index::sample(&mut self.rng, route.len() + 1, 3).into_vec();
+            cuts.sort();
+            double_bridge(route, cuts[0], cuts[1], cuts[2]);
+        }
+        let k = routes.len();
+        if k > 1 {
+            let from = self.rng.gen_range(0..k);
+            let to = (from + self.rng.gen_range(1..k)) % k;
+            if !routes[from].is_empty() {
+                let i = self.rng.gen_range(0..routes[from].len());
+                let j = self.rng.gen_range(0..=routes[to].len());
+                let mut to_route = routes[to].clone();
+                relocate(&mut routes[from], &mut to_route, i, j);
+                routes[to] = to_route;
+            }
+        }
+        if !dropped.is_empty() && k > 0 {
+            let location = dropped.swap_remove(self.rng.gen_range(0..dropped.len()));
+            let r = self.rng.gen_range(0..k);
+            let j = self.rng.gen_range(0..=routes[r].len());
+            insert(&mut routes[r], j, location);
+        }
+        MultiSolution {
+            dropped,
+            ..self.penalizer.penalize_routes(routes, false)
+        }
     }
 
     fn run_move(
         &mut self,
         local_move: &mut dyn FnMut(&mut Route, usize, usize),
+        distance_delta: &dyn Fn(&DistancePenalizer, &DistanceCache, usize, usize) -> i64,
         min_margin: usize,
     ) -> bool {
         let mut improved = false;
-        for i in 0..self.n {
-            for j in i + 1 + min_margin..self.n {
-                let mut new_route = self.current_solution.route.clone();
-                local_move(&mut new_route, i, j);
-                let new_solution = self.penalizer.penalize(new_route, false);
-                if self
-                    .penalizer
-                    .is_better(&new_solution, &self.current_solution)
-                {
-                    self.current_solution = new_solution;
-                    improved = true;
+        for r in 0..self.current_solution.routes.len() {
+            let n = self.current_solution.routes[r].route.len();
+            for i in 0..n {
+                if !self.time_left() {
+                    return improved;
+                }
+                for j in i + 1 + min_margin..n {
+                    let delta = distance_delta(
+                        &self.penalizer.distance_penalizer,
+                        &self.current_solution.routes[r].distance_cache,
+                        i,
+                        j,
+                    );
+                    // all moves leave the route before position i untouched
+                    improved |= self.try_move(r, &mut |route| local_move(route, i, j), delta, i);
+                }
+            }
+        }
+        improved
+    }
+
+    /// Runs a 3-opt reconnection for all 0 <= i < j <= k < n with segments of at most
+    /// `THREE_OPT_MAX_SEGMENT` locations, which still takes n * THREE_OPT_MAX_SEGMENT^2 evaluations,
+    /// so it is only used once the other moves fail and stops as soon as the time is up.
+    fn run_three_opt(&mut self, reconnection: ThreeOpt) -> bool {
+        let mut improved = false;
+        for r in 0..self.current_solution.routes.len() {
+            let n = self.current_solution.routes[r].route.len();
+            for i in 0..n {
+                if !self.time_left() {
+                    return improved;
+                }
+                for j in i + 1..n.min(i + 1 + THREE_OPT_MAX_SEGMENT) {
+                    for k in j..n.min(j + THREE_OPT_MAX_SEGMENT) {
+                        let delta = self.penalizer.distance_penalizer.three_opt_delta(
+                            &self.current_solution.routes[r].distance_cache,
+                            i,
+                            j,
+                            k,
+                            reconnection,
+                        );
+                        improved |= self.try_move(
+                            r,
+                            &mut |route| three_opt(route, i, j, k, reconnection),
+                            delta,
+                            i,
+                        );
+                    }
+                }
+            }
+        }
+        improved
+    }
+
+    /// Applies `local_move` to the r-th route if this improves the solution.
+    /// The move changes the route only from position `from` on and the distance by `distance_delta`.
+    fn try_move(
+        &mut self,
+        r: usize,
+        local_move: &mut dyn FnMut(&mut Route),
+        distance_delta: i64,
+        from: usize,
+    ) -> bool {
+        self.try_move_dropping(r, local_move, distance_delta, from, None)
+    }
+
+    /// Like `try_move`, for a move that leaves the given jobs dropped, if it changes them.
+    fn try_move_dropping(
+        &mut self,
+        r: usize,
+        local_move: &mut dyn FnMut(&mut Route),
+        distance_delta: i64,
+        from: usize,
+        dropped: Option<Vec<usize>>,
+    ) -> bool {
+        let solution = &self.current_solution.routes[r];
+        let (distance, time_report, capacity_report) =
+            self.penalizer
+                .penalize_move(solution, local_move, distance_delta, from);
+        let (total_distance, total_time_report, total_capacity_report) = self
+            .current_solution
+            .totals_with(&[(r, distance, time_report.as_ref(), capacity_report.as_ref())]);
+        if !self.penalizer.is_better_report(
+            total_distance,
+            total_time_report.as_ref(),
+            total_capacity_report.as_ref(),
+            dropped.as_deref().unwrap_or(&self.current_solution.dropped),
+            self.current_solution.distance,
+            self.current_solution.time_report.as_ref(),
+            self.current_solution.capacity_report.as_ref(),
+            &self.current_solution.dropped,
+        ) {
+            return false;
+        }
+        let mut new_route = solution.route.clone();
+        local_move(&mut new_route);
+        let new_solution = self.penalizer.penalize(new_route, false);
+        self.current_solution.set_route(r, new_solution);
+        if let Some(dropped) = dropped {
+            self.current_solution.dropped = dropped;
+        }
+        true
+    }
+
+    /// Drops every optional job whose removal improves the solution.
+    fn run_remove(&mut self) -> bool {
+        let mut improved = false;
+        for r in 0..self.current_solution.routes.len() {
+            let mut i = 0;
+            while i < self.current_solution.routes[r].route.len() {
+                let location = self.current_solution.routes[r].route[i];
+                if self.penalizer.is_optional(location) {
+                    let delta = self
+                        .penalizer
+                        .distance_penalizer
+                        .remove_delta(&self.current_solution.routes[r].distance_cache, i);
+                    let mut dropped = self.current_solution.dropped.clone();
+                    dropped.push(location);
+                    if self.try_move_dropping(
+                        r,
+                        &mut |route| remove(route, i),
+                        delta,
+                        i,
+                        Some(dropped),
+                    ) {
+                        // the next location moved up to position i
+                        improved = true;
+                        continue;
+                    }
                 }
+                i += 1;
             }
         }
         improved
     }
+
+    /// Inserts every dropped job at the first position of any route where this improves the solution.
+    fn run_add(&mut self) -> bool {
+        let mut improved = false;
+        let mut d = 0;
+        'jobs: while d < self.current_solution.dropped.len() {
+            let location = self.current_solution.dropped[d];
+            let mut dropped = self.current_solution.dropped.clone();
+            dropped.remove(d);
+            for r in 0..self.current_solution.routes.len() {
+                for j in 0..=self.current_solution.routes[r].route.len() {
+                    let delta = self.penalizer.distance_penalizer.insert_delta(
+                        &self.current_solution.routes[r].distance_cache,
+                        j,
+                        location,
+                    );
+                    if self.try_move_dropping(
+                        r,
+                        &mut |route| insert(route, j, location),
+                        delta,
+                        j,
+                        Some(dropped.clone()),
+                    ) {
+                        // the next dropped job moved up to position d
+                        improved = true;
+                        continue 'jobs;
+                    }
+                }
+            }
+            d += 1;
+        }
+        improved
+    }
+
+    /// Runs a move between every pair of routes.
+    /// The first position runs in 0..len1 + extra_i, the second in 0..len2 + extra_j.
+    /// If the move is symmetric, each pair of routes is only tried in one order.
+    fn run_inter_move(
+        &mut self,
+        local_move: &mut dyn FnMut(&mut Route, &mut Route, usize, usize),
+        distance_deltas: &InterDistanceDelta,
+        extra_i: usize,
+        extra_j: usize,
+        symmetric: bool,
+    ) -> bool {
+        let mut improved = false;
+        let k = self.current_solution.routes.len();
+        for r1 in 0..k {
+            for r2 in 0..k {
+                if r1 == r2 || (symmetric && r2 < r1) {
+                    continue;
+                }
+                // the lengths of the routes change with every accepted move, hence no for loops
+                let mut i = 0;
+                while i < self.current_solution.routes[r1].route.len() + extra_i {
+                    let mut j = 0;
+                    while i < self.current_solution.routes[r1].route.len() + extra_i
+                        && j < self.current_solution.routes[r2].route.len() + extra_j
+                    {
+                        let deltas = distance_deltas(
+                            &self.penalizer.distance_penalizer,
+                            &self.current_solution.routes[r1].distance_cache,
+                            &self.current_solution.routes[r2].distance_cache,
+                            i,
+                            j,
+                        );
+                        // the schedules only have to be simulated from the changed positions on,
+                        // and the routes and their caches are only built when needed
+                        let [evaluation1, evaluation2] = self.penalizer.penalize_inter_move(
+                            [
+                                &self.current_solution.routes[r1],
+                                &self.current_solution.routes[r2],
+                            ],
+                            &mut |route1, route2| local_move(route1, route2, i, j),
+                            deltas,
+                            [i, j],
+                        );
+                        let (distance1, time_report1, capacity_report1) = evaluation1;
+                        let (distance2, time_report2, capacity_report2) = evaluation2;
+                        let (total_distance, total_time_report, total_capacity_report) =
+                            self.current_solution.totals_with(&[
+                                (
+                                    r1,
+                                    distance1,
+                                    time_report1.as_ref(),
+                                    capacity_report1.as_ref(),
+                                ),
+                                (
+                                    r2,
+                                    distance2,
+                                    time_report2.as_ref(),
+                                    capacity_report2.as_ref(),
+                                ),
+                            ]);
+                        if self.penalizer.is_better_report(
+                            total_distance,
+                            total_time_report.as_ref(),
+                            total_capacity_report.as_ref(),
+                            &self.current_solution.dropped,
+                            self.current_solution.distance,
+                            self.current_solution.time_report.as_ref(),
+                            self.current_solution.capacity_report.as_ref(),
+                            &self.current_solution.dropped,
+                        ) {
+                            let mut new_route1 = self.current_solution.routes[r1].route.clone();
+                            let mut new_route2 = self.current_solution.routes[r2].route.clone();
+                            local_move(&mut new_route1, &mut new_route2, i, j);
+                            let new_solution1 = self.penalizer.penalize(new_route1, false);
+                            let new_solution2 = self.penalizer.penalize(new_route2, false);
+                            self.current_solution.set_route(r1, new_solution1);
+                            self.current_solution.set_route(r2, new_solution2);
+                            improved = true;
+                        }
+                        j += 1;
+                    }
+                    i += 1;
+                }
+            }
+        }
+        improved
+    }
+
     fn run_heuristics(&mut self) -> bool {
         let mut improved = false;
-        improved |= self.run_move(&mut two_opt, 0);
+        improved |= self.run_move(&mut two_opt, &DistancePenalizer::two_opt_delta, 0);
         // for 0 and 1, we have the same move as for 2opt
-        improved |= self.run_move(&mut swap, 2);
+        improved |= self.run_move(&mut swap, &DistancePenalizer::swap_delta, 2);
         // for 0, it is like swapping neighbors
-        improved |= self.run_move(&mut one_shift_left, 1);
-        improved |= self.run_move(&mut one_shift_right, 1);
+        improved |= self.run_move(
+            &mut one_shift_left,
+            &|penalizer, cache, i, j| penalizer.shift_left_delta(cache, i, j, 1),
+            1,
+        );
+        improved |= self.run_move(
+            &mut one_shift_right,
+            &|penalizer, cache, i, j| penalizer.shift_right_delta(cache, i, j, 1),
+            1,
+        );
         // 0 would be a two city intervall being rotated by 2, so no change
         // 1 would be like a 3 city intervall being rotated by 1 in the other direction
-        improved |= self.run_move(&mut two_shift_left, 2);
+        improved |= self.run_move(
+            &mut two_shift_left,
+            &|penalizer, cache, i, j| penalizer.shift_left_delta(cache, i, j, 2),
+            2,
+        );
         // 2 would be like a 4 city intervall being roated by 2, already done in other direction
-        improved |= self.run_move(&mut two_shift_right, 3);
+        improved |= self.run_move(
+            &mut two_shift_right,
+            &|penalizer, cache, i, j| penalizer.shift_right_delta(cache, i, j, 2),
+            3,
+        );
 
         // 0 would lead to an error.
         // 1 would be a 3 city intervall being rotated by 3, so no change.
         // 2 would be a 4 city intervall being rotated by 1 in the other direction
         // 3 would be a 5 city intervall being rotated by 2 in the other direction
-        improved |= self.run_move(&mut three_shift_left, 4);
+        improved |= self.run_move(
+            &mut three_shift_left,
+            &|penalizer, cache, i, j| penalizer.shift_left_delta(cache, i, j, 3),
+            4,
+        );
         // 4 would be like a 6 city intervall being roated by 3, already done in other direction
-        improved |= self.run_move(&mut three_shift_right, 5);
+        improved |= self.run_move(
+            &mut three_shift_right,
+            &|penalizer, cache, i, j| penalizer.shift_right_delta(cache, i, j, 3),
+            5,
+        );
+        // Or-opt: moving a single location reversed is just a shift,
+        // and moving a segment of k reversed needs at least one other location in between
+        for k in 2..=3 {
+            improved |= self.run_move(
+                &mut |route, i, j| reversed_shift_left(route, i, j, k),
+                &|penalizer, cache, i, j| penalizer.reversed_shift_left_delta(cache, i, j, k),
+                k - 1,
+            );
+            improved |= self.run_move(
+                &mut |route, i, j| reversed_shift_right(route, i, j, k),
+                &|penalizer, cache, i, j| penalizer.reversed_shift_right_delta(cache, i, j, k),
+                k - 1,
+            );
+        }
+
+        if self.current_solution.routes.len() > 1 {
+            // a location can be inserted behind the last location of the other route
+            improved |= self.run_inter_move(
+                &mut relocate,
+                &DistancePenalizer::relocate_delta,
+                0,
+                1,
+                false,
+            );
+            improved |= self.run_inter_move(
+                &mut exchange,
+                &DistancePenalizer::exchange_delta,
+                0,
+                0,
+                true,
+            );
+            // empty tails are allowed, so one route can take over the complete tail of the other
+            improved |=
+                self.run_inter_move(&mut cross, &DistancePenalizer::cross_delta, 1, 1, true);
+        }
+        improved |= self.run_remove();
+        improved |= self.run_add();
+        if !improved {
+            for reconnection in [
+                ThreeOpt::ReverseBoth,
+                ThreeOpt::ReverseFirst,
+                ThreeOpt::ReverseSecond,
+            ] {
+                improved |= self.run_three_opt(reconnection);
+            }
+        }
         improved
     }
 
-    fn termination_criterion(&self) -> bool {
-        // returns true if the termination criterion is met
+    /// The value of each level of the objective function for the best solution,
+    /// beginning with the hard components.
+    pub fn objective(&self) -> Vec<f64> {
+        let levels = self.penalizer.objective_function.level_count();
+        self.penalizer.objective_multi(&self.best_solution)[..levels].to_vec()
+    }
+
+    /// The optional jobs the best solution drops, each with the reason why:
+    /// the component that visiting it worsens the most, for the best position in any route.
+    pub fn dropped_jobs(&self) -> Vec<DroppedJob> {
+        let solution = &self.best_solution;
+        let components = self.penalizer.components(
+            solution.distance,
+            solution.time_report.as_ref(),
+            solution.capacity_report.as_ref(),
+            &solution.dropped,
+        );
+        solution
+            .dropped
+            .iter()
+            .map(|&location| {
+                let dropped: Vec<usize> = solution
+                    .dropped
+                    .iter()
+                    .copied()
+                    .filter(|&other| other != location)
+                    .collect();
+                let visited = (0..solution.routes.len())
+                    .flat_map(|r| (0..=solution.routes[r].route.len()).map(move |j| (r, j)))
+                    .map(|(r, j)| {
+                        let mut route = solution.routes[r].route.clone();
+                        insert(&mut route, j, location);
+                        let new_solution = self.penalizer.penalize(route, false);
+                        let (distance, time_report, capacity_report) = solution.totals_with(&[(
+                            r,
+                            new_solution.distance,
+                            new_solution.time_report.as_ref(),
+                            new_solution.capacity_report.as_ref(),
+                        )]);
+                        self.penalizer.components(
+                            distance,
+                            time_report.as_ref(),
+                            capacity_report.as_ref(),
+                            &dropped,
+                        )
+                    })
+                    .min_by(|a, b| {
+                        let objective =
+                            |components| self.penalizer.objective_function.evaluate(components);
+                        // compared level by level, without a panic on NaN
+                        objective(a)
+                            .iter()
+                            .zip(&objective(b))
+                            .map(|(a, b)| a.total_cmp(b))
+                            .find(|ordering| ordering.is_ne())
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                DroppedJob {
+                    location,
+                    priority: self.penalizer.priorities[location].unwrap_or_default(),
+                    reason: visited.and_then(|visited| {
+                        self.penalizer
+                            .objective_function
+                            .worsened(&visited, &components)
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// The precedences between jobs that the best solution violates.
+    pub fn precedence_violations(&self) -> Vec<PrecedenceViolation> {
+        match (
+            &self.penalizer.time_penalizer,
+            &self.best_solution.time_report,
+        ) {
+            (Some(time_penalizer), Some(time_report)) => {
+                time_penalizer.precedence_violations(time_report)
+            }
+            _ => vec![],
+        }
+    }
+
+    fn time_left(&self) -> bool {
         // no time limit means we always continue
         match self.time_limit {
             Some(limit) => chrono::Utc::now() - self.start <= limit,
@@ -117,47 +631,115 @@ impl Solver {
         }
     }
 
+    fn termination_criterion(&self) -> bool {
+        // returns true if the termination criterion is met
+        // no iteration limit means we continue until the time is up
+        let iterations_left = match self.iteration_limit {
+            Some(limit) => self.iterations < limit,
+            None => true,
+        };
+        iterations_left && self.time_left()
+    }
+
     fn one_time(&self) -> bool {
-        self.time_limit.is_none()
+        self.time_limit.is_none() && self.iteration_limit.is_none()
+    }
+
+    /// Finds the shortest route by dynamic programming, which is optimal
+    /// as long as only the distance of a single route is minimized.
+    fn solve_exactly(&mut self) {
+        let route = exact::optimal_route(
+            self.penalizer.distance_penalizer.distance_matrix(),
+            &self.jobs,
+            self.vehicles
+                .as_ref()
+                .and_then(|vehicles| vehicles.first().copied()),
+        );
+        self.best_solution = self.penalizer.penalize_routes(vec![route], true);
+        self.optimal = true;
+        self.time_taken = chrono::Utc::now() - self.start;
     }
 
     pub fn solve(&mut self) {
-        let mut improved = true;
         self.start = chrono::Utc::now();
+        if self.exact {
+            self.solve_exactly();
+            return;
+        }
         while self.termination_criterion() {
             self.iterations += 1;
-            improved = true;
-            while improved & self.termination_criterion() {
+            let mut improved = true;
+            // the iteration limit only counts restarts, so each local search runs to its end
+            while improved & self.time_left() {
                 improved = self.run_heuristics()
             }
 
             if self
                 .penalizer
-                .is_better(&self.current_solution, &self.best_solution)
+                .is_better_multi(&self.current_solution, &self.best_solution)
             {
                 self.best_solution = self.current_solution.clone();
             }
-            self.current_solution = self.generate_initial_solution();
+            self.current_solution = self.next_solution();
 
             if self.one_time() {
                 break;
             }
         }
         // finally, we also build the schedule
-        self.best_solution = self
-            .penalizer
-            .penalize(self.best_solution.route.clone(), true);
+        let routes = self
+            .best_solution
+            .routes
+            .iter()
+            .map(|solution| solution.route.clone())
+            .collect();
+        self.best_solution = MultiSolution {
+            dropped: self.best_solution.dropped.clone(),
+            ..self.penalizer.penalize_routes(routes, true)
+        };
         self.time_taken = chrono::Utc::now() - self.start;
     }
 }
 
+fn is_depot(location: usize, vehicles: &[Vehicle]) -> bool {
+    vehicles.iter().any(|vehicle| vehicle.is_depot(location))
+}
+
+/// Splits a sequence of locations into one route per vehicle, keeping the order.
+/// Depots of the vehicles are not part of any route.
+/// Without vehicles, the whole sequence forms a single closed tour.
+fn split_sequence(sequence: Vec<usize>, vehicles: &Option<Vec<Vehicle>>) -> Vec<Route> {
+    match vehicles {
+        None => vec![Route::new(sequence)],
+        Some(vehicles) => {
+            let jobs: Vec<usize> = sequence
+                .into_iter()
+                .filter(|&location| !is_depot(location, vehicles))
+                .collect();
+            let k = vehicles.len();
+            vehicles
+                .iter()
+                .enumerate()
+                .map(|(v, &vehicle)| {
+                    Route::with_vehicle(
+                        jobs[v * jobs.len() / k..(v + 1) * jobs.len() / k].to_vec(),
+                        vehicle,
+                    )
+                })
+                .collect()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::{NaiveTime, TimeZone, Utc};
 
     use crate::{
-        input,
+        input::error::InputError,
+        objective::{Component, ObjectiveFunction},
         penalties::{
+            capacity::capacity_input::CapacityInput,
             distance::DistanceMatrix,
             time::{
                 operation_times::OperationTimes,
@@ -165,8 +747,11 @@ mod tests {
                 time_windows::{TimeWindow, TimeWindows},
             },
         },
+        schema::Problem,
     };
 
+    use std::collections::BTreeMap;
+
     use super::*;
 
     #[test]
@@ -176,7 +761,133 @@ mod tests {
         let mut solver = Solver::new(input);
         solver.solve();
         assert_eq!(solver.best_solution.distance, 541);
-        assert_eq!(solver.best_solution.route.sequence, vec![1, 0, 2]);
+        assert_eq!(solver.best_solution.routes[0].route.sequence, vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_solver_vehicles() {
+        // two depots at -10 and 10 with one job next to each of them
+        let positions: Vec<i64> = vec![-10, 10, -9, 9];
+        let matrix = DistanceMatrix::new(
+            positions
+                .iter()
+                .map(|a| positions.iter().map(|b| a.abs_diff(*b)).collect())
+                .collect(),
+        );
+        let input = Input {
+            vehicles: Some(vec![Vehicle::new(0, 0), Vehicle::new(1, 1)]),
+            ..Input::new(matrix, None, None, Some(Route::new(vec![3, 2])))
+        };
+        let mut solver = Solver::new(input);
+        assert_eq!(solver.best_solution.distance, 76);
+        solver.solve();
+        assert_eq!(solver.best_solution.distance, 4);
+        assert_eq!(solver.best_solution.sequences(), vec![vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn test_solver_open_route() {
+        // home at 0 and the office at 10, with jobs in between
+        let positions: Vec<i64> = vec![0, 10, 5, 1, 3, 8];
+        let matrix = || {
+            DistanceMatrix::new(
+                positions
+                    .iter()
+                    .map(|a| positions.iter().map(|b| a.abs_diff(*b)).collect())
+                    .collect(),
+            )
+        };
+        let solve = |vehicle| {
+            let input = Input {
+                vehicles: Some(vec![vehicle]),
+                ..Input::new(matrix(), None, None, None)
+            };
+            let mut solver = Solver::new(input);
+            solver.solve();
+            solver.best_solution
+        };
+        // ending wherever the last job is
+        let solution = solve(Vehicle::with_endpoints(Some(0), None));
+        assert_eq!(solution.sequences(), vec![vec![3, 4, 2, 5, 1]]);
+        assert_eq!(solution.distance, 10);
+        // starting at home and ending at the office
+        let solution = solve(Vehicle::new(0, 1));
+        assert_eq!(solution.sequences(), vec![vec![3, 4, 2, 5]]);
+        assert_eq!(solution.distance, 10);
+        // starting at the first job and ending at home
+        let solution = solve(Vehicle::with_endpoints(None, Some(0)));
+        assert_eq!(solution.sequences(), vec![vec![1, 5, 2, 4, 3]]);
+        assert_eq!(solution.distance, 10);
+    }
+
+    #[test]
+    fn test_solver_seed() {
+        let n: usize = 12;
+        let matrix = || {
+            DistanceMatrix::new(
+                (0..n)
+                    .map(|i| (0..n).map(|j| ((i * 17 + j * 29) % 23) as u64).collect())
+                    .collect(),
+            )
+        };
+        let solve = |seed| {
+            let input = Input {
+                iteration_limit: Some(5),
+                seed: Some(seed),
+                ..Input::new(matrix(), None, None, None)
+            };
+            let mut solver = Solver::new(input);
+            solver.solve();
+            assert_eq!(solver.iterations, 5);
+            solver.best_solution.sequences()
+        };
+        assert_eq!(solve(42), solve(42));
+    }
+
+    #[test]
+    fn test_solver_acceptance() {
+        let n: usize = 12;
+        let matrix = || {
+            DistanceMatrix::new(
+                (0..n)
+                    .map(|i| (0..n).map(|j| ((i * 17 + j * 29) % 23) as u64).collect())
+                    .collect(),
+            )
+        };
+        let solve = |acceptance, vehicles| {
+            let input = Input {
+                iteration_limit: Some(20),
+                seed: Some(7),
+                acceptance,
+                vehicles,
+                ..Input::new(matrix(), None, None, None)
+            };
+            let mut solver = Solver::new(input);
+            solver.solve();
+            assert_eq!(solver.iterations, 20);
+            let mut locations: Vec<usize> = solver
+                .best_solution
+                .sequences()
+                .into_iter()
+                .flatten()
+                .collect();
+            locations.sort();
+            (solver.best_solution.sequences(), locations)
+        };
+        for acceptance in [
+            Acceptance::SimulatedAnnealing {
+                initial_temperature: 0.05,
+                cooling_rate: 0.9,
+            },
+            Acceptance::LateAcceptance { history_length: 5 },
+        ] {
+            for vehicles in [None, Some(vec![Vehicle::new(0, 0), Vehicle::new(1, 1)])] {
+                let depots = vehicles.as_ref().map_or(0, |vehicles| vehicles.len());
+                let (sequences, locations) = solve(acceptance, vehicles.clone());
+                assert_eq!(locations, (depots..n).collect::<Vec<_>>());
+                assert_eq!(solve(acceptance, vehicles).0, sequences);
+            }
+        }
     }
 
     #[test]
@@ -190,7 +901,107 @@ mod tests {
         assert!(end - start >= TimeDelta::milliseconds(100));
         assert!(end - start < TimeDelta::milliseconds(150));
         assert_eq!(solver.best_solution.distance, 541);
-        assert_eq!(solver.best_solution.route.sequence, vec![1, 0, 2]);
+        assert_eq!(solver.best_solution.routes[0].route.sequence, vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn test_solver_time_limit_large() {
+        // 150 locations on a circle with time windows, so every move simulates the schedule
+        let n = 150;
+        let position = |location: usize| (location * 7 % n) as u64;
+        let distances: Vec<Vec<u64>> = (0..n)
+            .map(|a| {
+                (0..n)
+                    .map(|b| {
+                        let d = position(a).abs_diff(position(b));
+                        d.min(n as u64 - d)
+                    })
+                    .collect()
+            })
+            .collect();
+        let input = Problem {
+            duration_matrix: Some(
+                distances
+                    .iter()
+                    .map(|row| row.iter().map(|d| d * 60).collect())
+                    .collect(),
+            ),
+            job_durations: Some(vec![600; n]),
+            time_windows: Some(vec![Some(vec![(1735718400, 1736323200)]); n]),
+            distance_matrix: distances,
+            time_limit: Some(1),
+            seed: Some(1),
+            ..Problem::default()
+        }
+        .into_input()
+        .unwrap();
+        let mut solver = Solver::new(input);
+        let start = chrono::Utc::now();
+        solver.solve();
+        assert!(chrono::Utc::now() - start < TimeDelta::milliseconds(1500));
+    }
+
+    #[test]
+    fn test_solver_exact() {
+        // a grid of 4 x 4 locations, whose shortest tour goes around without crossing itself
+        let positions: Vec<(i64, i64)> = (0..16).map(|i| (i / 4, i % 4)).collect();
+        let matrix = || {
+            DistanceMatrix::new(
+                positions
+                    .iter()
+                    .map(|a| {
+                        positions
+                            .iter()
+                            .map(|b| {
+                                (10.0 * (((a.0 - b.0).pow(2) + (a.1 - b.1).pow(2)) as f64).sqrt())
+                                    as u64
+                            })
+                            .collect()
+                    })
+                    .collect(),
+            )
+        };
+        // small instances that only minimize the distance are solved exactly on request
+        let input = Input {
+            exact: true,
+            ..Input::new(matrix(), None, None, None)
+        };
+        let mut solver = Solver::new(input);
+        solver.solve();
+        assert!(solver.optimal);
+        assert_eq!(solver.iterations, 0);
+        assert_eq!(solver.best_solution.distance, 160);
+        // and so is a single vehicle, whose depot is not part of its route
+        let input = Input {
+            vehicles: Some(vec![Vehicle::with_endpoints(Some(0), None)]),
+            exact: true,
+            ..Input::new(matrix(), None, None, None)
+        };
+        let mut solver = Solver::new(input);
+        solver.solve();
+        assert!(solver.optimal);
+        assert_eq!(solver.best_solution.distance, 150);
+        assert_eq!(solver.best_solution.routes[0].route.len(), 15);
+        // the local search runs by default
+        let mut solver = Solver::new(Input::new(matrix(), None, None, None));
+        solver.solve();
+        assert!(!solver.optimal);
+        assert_eq!(solver.iterations, 1);
+        // and instances that cannot be solved exactly are rejected on request
+        let input = Problem {
+            distance_matrix: matrix().matrix().to_vec(),
+            vehicles: Some(vec![(Some(0), Some(0)), (Some(15), Some(15))]),
+            exact: true,
+            ..Problem::default()
+        }
+        .into_input();
+        assert_eq!(
+            input.err(),
+            Some(InputError::ConflictingOptions {
+                field: "exact",
+                other: "vehicles"
+            })
+        );
     }
 
     #[test]
@@ -249,11 +1060,263 @@ mod tests {
             ],
             travel_duration_until_break: None,
             break_duration: None,
+            ..TimeInput::default()
         });
         let input = Input::new(distance_matrix, time_input, None, None);
         let mut solver = Solver::new(input);
         solver.solve();
-        assert_eq!(solver.best_solution.route.sequence, vec![2, 1, 0]);
+        assert_eq!(solver.best_solution.routes[0].route.sequence, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_solver_objective() {
+        // the jobs form a chain with the last one close to the start, which has to be visited early
+        let distances = vec![
+            vec![0, 1, 2, 2],
+            vec![1, 0, 1, 2],
+            vec![2, 1, 0, 1],
+            vec![2, 2, 1, 0],
+        ];
+        let solve = |objective: Option<Vec<BTreeMap<String, f64>>>| {
+            let input = Problem {
+                distance_matrix: distances.clone(),
+                duration_matrix: Some(
+                    distances
+                        .iter()
+                        .map(|row| row.iter().map(|hours| hours * 3600).collect())
+                        .collect(),
+                ),
+                job_durations: Some(vec![0; 4]),
+                time_windows: Some(vec![
+                    Some(vec![(1735718400, 1735747200)]),
+                    Some(vec![(1735718400, 1735747200)]),
+                    Some(vec![(1735718400, 1735747200)]),
+                    Some(vec![(1735718400, 1735725600)]),
+                ]),
+                operation_times: Some((28800, 72000)),
+                start_location: Some(0),
+                open_route: true,
+                objective,
+                ..Problem::default()
+            }
+            .into_input()
+            .unwrap();
+            let mut solver = Solver::new(input);
+            solver.solve();
+            (
+                solver.best_solution.routes[0].route.sequence.clone(),
+                solver.objective(),
+            )
+        };
+        let weighted = |lateness: f64| {
+            Some(vec![BTreeMap::from([
+                ("lateness".to_string(), lateness),
+                ("distance".to_string(), 1.0),
+            ])])
+        };
+        // following the chain takes 3 units of distance, but is one hour late,
+        // visiting the last job first is on time, but takes 4 units of distance
+        assert_eq!(solve(None).0, vec![3, 2, 1]);
+        // one hour of lateness is worth half a unit of distance
+        assert_eq!(
+            solve(weighted(1.0 / 7200.0)),
+            (vec![1, 2, 3], vec![0.0, 0.0, 0.0, 0.0, 0.0, 3.5])
+        );
+        // one hour of lateness is worth two units of distance
+        assert_eq!(
+            solve(weighted(1.0 / 1800.0)),
+            (vec![3, 2, 1], vec![0.0, 0.0, 0.0, 0.0, 0.0, 4.0])
+        );
+    }
+
+    #[test]
+    fn test_solver_optional_jobs() {
+        // three jobs of two hours each, but only four hours until all of them are due
+        let solve = |priorities: Vec<Option<f64>>| {
+            let input = Problem {
+                distance_matrix: vec![vec![0, 1, 1], vec![1, 0, 1], vec![1, 1, 0]],
+                duration_matrix: Some(vec![vec![0; 3]; 3]),
+                job_durations: Some(vec![7200; 3]),
+                time_windows: Some(vec![Some(vec![(1735718400, 1735732800)]); 3]),
+                operation_times: Some((28800, 72000)),
+                priorities: Some(priorities),
+                ..Problem::default()
+            }
+            .into_input()
+            .unwrap();
+            let mut solver = Solver::new(input);
+            solver.solve();
+            let mut sequence = solver.best_solution.routes[0].route.sequence.clone();
+            sequence.sort();
+            (sequence, solver.dropped_jobs())
+        };
+        let (sequence, dropped) = solve(vec![None, None, Some(1.0)]);
+        assert_eq!(sequence, vec![0, 1]);
+        assert_eq!(
+            dropped,
+            vec![DroppedJob {
+                location: 2,
+                priority: 1.0,
+                reason: Some(Component::Lateness)
+            }]
+        );
+        // the job with the lower priority is dropped
+        let (sequence, dropped) = solve(vec![None, Some(1.0), Some(5.0)]);
+        assert_eq!(sequence, vec![0, 2]);
+        assert_eq!(dropped[0].location, 1);
+    }
+
+    #[test]
+    fn test_solver_prize_collecting() {
+        // a job far away is only visited if its priority makes up for the detour
+        let positions: Vec<i64> = vec![0, 1, 2, 100];
+        let solve = |priority| {
+            let input = Input {
+                objective: ObjectiveFunction::new(vec![vec![
+                    (Component::DroppedPriority, 1.0),
+                    (Component::Distance, 1.0),
+                ]]),
+                priorities: Some(vec![None, None, None, Some(priority)]),
+                // the initial route already leaves out the optional job
+                ..Input::new(
+                    DistanceMatrix::new(
+                        positions
+                            .iter()
+                            .map(|a| positions.iter().map(|b| a.abs_diff(*b)).collect())
+                            .collect(),
+                    ),
+                    None,
+                    None,
+                    Some(Route::new(vec![0, 1, 2])),
+                )
+            };
+            let mut solver = Solver::new(input);
+            solver.solve();
+            (
+                solver.best_solution.distance,
+                solver.best_solution.dropped.clone(),
+                solver.dropped_jobs(),
+            )
+        };
+        let (distance, dropped, dropped_jobs) = solve(150.0);
+        assert_eq!((distance, dropped), (4, vec![3]));
+        assert_eq!(dropped_jobs[0].reason, Some(Component::Distance));
+        let (distance, dropped, _) = solve(250.0);
+        assert_eq!((distance, dropped), (200, vec![]));
+    }
+
+    #[test]
+    fn test_solver_capacity() {
+        // the depot at 0 with jobs at 10, 11, -10 and -11
+        let positions: Vec<i64> = vec![0, 10, 11, -10, -11];
+        let input = |capacity_input| Input {
+            vehicles: Some(vec![Vehicle::new(0, 0), Vehicle::new(0, 0)]),
+            capacity_input: Some(capacity_input),
+            seed: Some(1),
+            ..Input::new(
+                DistanceMatrix::new(
+                    positions
+                        .iter()
+                        .map(|a| positions.iter().map(|b| a.abs_diff(*b)).collect())
+                        .collect(),
+                ),
+                None,
+                None,
+                None,
+            )
+        };
+        // what is picked up on one side is brought to the other side,
+        // which one vehicle can do for both pairs, one after the other
+        let mut solver = Solver::new(input(CapacityInput::new(
+            vec![vec![0], vec![-1], vec![1], vec![-1], vec![1]],
+            Some(vec![1]),
+            vec![(1, 4), (3, 2)],
+        )));
+        solver.solve();
+        let capacity_report = solver.best_solution.capacity_report.as_ref().unwrap();
+        assert!(capacity_report.is_feasible());
+        assert_eq!(solver.best_solution.distance, 64);
+        // and the other one stays at the depot
+        assert!(solver.best_solution.sequences().contains(&vec![]));
+        // a single vehicle cannot deliver to both sides at once
+        let mut solver = Solver::new(input(CapacityInput::new(
+            vec![vec![0], vec![1], vec![1], vec![1], vec![1]],
+            Some(vec![2]),
+            vec![],
+        )));
+        solver.solve();
+        assert_eq!(
+            solver
+                .best_solution
+                .capacity_report
+                .as_ref()
+                .unwrap()
+                .overload,
+            0
+        );
+        assert_eq!(solver.best_solution.distance, 44);
+        // a vehicle serving both jobs on one side would drive 20 units less, but overload,
+        // which even an objective of only the distance does not trade for it
+        let mut solver = Solver::new(Input {
+            objective: ObjectiveFunction::new(vec![vec![(Component::Distance, 1.0)]]),
+            ..input(CapacityInput::new(
+                vec![vec![0], vec![1], vec![1], vec![0], vec![0]],
+                Some(vec![1]),
+                vec![],
+            ))
+        });
+        solver.solve();
+        let capacity_report = solver.best_solution.capacity_report.as_ref().unwrap();
+        assert_eq!(capacity_report.overload, 0);
+        assert_eq!(solver.best_solution.distance, 64);
+    }
+
+    #[test]
+    fn test_solver_precedences() {
+        // the depot at 0 with jobs at 10 and 20, both directions are equally long
+        let positions: Vec<u64> = vec![0, 10, 20];
+        let matrix: Vec<Vec<u64>> = positions
+            .iter()
+            .map(|a| positions.iter().map(|b| a.abs_diff(*b)).collect())
+            .collect();
+        let solve = |precedences| {
+            let input = Problem {
+                distance_matrix: matrix.clone(),
+                duration_matrix: Some(matrix.clone()),
+                job_durations: Some(vec![0, 0, 0]),
+                time_windows: Some(vec![None, None, None]),
+                vehicles: Some(vec![(Some(0), Some(0))]),
+                iteration_limit: Some(1000),
+                seed: Some(1),
+                start_time: Some(1735689600),
+                precedences: Some(precedences),
+                ..Problem::default()
+            }
+            .into_input()
+            .unwrap();
+            let mut solver = Solver::new(input);
+            solver.solve();
+            solver
+        };
+        // the job at 20 comes first, and the vehicle waits an hour before the one at 10
+        let solver = solve(vec![(2, 1, Some(3600), None)]);
+        assert_eq!(solver.best_solution.sequences(), vec![vec![2, 1]]);
+        assert!(solver.precedence_violations().is_empty());
+        assert_eq!(
+            solver
+                .best_solution
+                .time_report
+                .as_ref()
+                .unwrap()
+                .waiting_time,
+            chrono::Duration::seconds(3590)
+        );
+        // the vehicle cannot get from one job to the other within five seconds
+        let solver = solve(vec![(1, 2, None, Some(5))]);
+        let violations = solver.precedence_violations();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].precedence, 0);
+        assert_eq!(solver.objective()[1], 1.0);
     }
 
     #[test]
@@ -261,24 +1324,22 @@ mod tests {
         let distance_matrix = vec![vec![0]];
         let duration_matrix = Some(vec![vec![0]]);
         let job_durations = Some(vec![10800]);
-        let time_windows = Some(vec![vec![(1735689600, 1736035200)]]);
+        let time_windows = Some(vec![Some(vec![(1735689600, 1736035200)])]);
         let operation_times = Some((0, 82800));
-        let input = input::get_input_from_raw(
+        let input = Problem {
             distance_matrix,
             duration_matrix,
             job_durations,
             time_windows,
             operation_times,
-            None,
-            None,
-            None,
-            None,
-            None,
-        );
+            ..Problem::default()
+        }
+        .into_input()
+        .unwrap();
         let mut solver = Solver::new(input);
         solver.solve();
         let solution = solver.best_solution.clone();
-        assert_eq!(solution.route.sequence, vec![0]);
+        assert_eq!(solution.routes[0].route.sequence, vec![0]);
         assert_eq!(
             chrono::DateTime::from_timestamp(1735689600, 0).unwrap(),
             chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()
@@ -292,4 +1353,36 @@ mod tests {
             chrono::Duration::zero()
         );
     }
+
+    #[test]
+    fn test_solve_around_the_clock() {
+        // working around the clock, only the time windows of a night shift matter
+        let night = 1735768800;
+        let input = Problem {
+            distance_matrix: vec![vec![0, 1, 1], vec![1, 0, 1], vec![1, 1, 0]],
+            duration_matrix: Some(vec![vec![1800; 3]; 3]),
+            job_durations: Some(vec![1800; 3]),
+            time_windows: Some(vec![
+                Some(vec![(night + 3600, night + 7200)]),
+                Some(vec![(night, night + 3600)]),
+                Some(vec![(night + 7200, night + 10800)]),
+            ]),
+            operation_times: Some((0, 86400)),
+            travel_duration_until_break: Some(3600),
+            break_duration: Some(900),
+            iteration_limit: Some(20),
+            seed: Some(1),
+            ..Problem::default()
+        }
+        .into_input()
+        .unwrap();
+        assert!(input.time_input.as_ref().unwrap().operation_times.is_none());
+        let mut solver = Solver::new(input);
+        solver.solve();
+        let solution = solver.best_solution.clone();
+        assert_eq!(solution.routes[0].route.sequence, vec![1, 0, 2]);
+        let time_report = solution.time_report.unwrap();
+        assert_eq!(time_report.lateness, chrono::Duration::zero());
+        assert_eq!(time_report.job_splits, 0);
+    }
 }
@@ -0,0 +1,33 @@
+use std::process::Command;
+
+use traveling_rustling::schema::SolutionReport;
+
+#[test]
+fn test_example_input() {
+    let output = Command::new(env!("CARGO_BIN_EXE_traveling_rustling"))
+        .args(["data/example_input.json", "-s", "7", "-t", "1"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report = SolutionReport::from_json(&String::from_utf8(output.stdout).unwrap()).unwrap();
+    // without vehicles, a single tour visits every location of the example
+    let mut locations: Vec<usize> = report
+        .routes
+        .iter()
+        .flat_map(|route| {
+            route
+                .start
+                .into_iter()
+                .chain(route.route.iter().copied())
+                .chain(route.end)
+        })
+        .collect();
+    locations.sort();
+    locations.dedup();
+    assert_eq!(locations, (0..13).collect::<Vec<_>>());
+}